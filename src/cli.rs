@@ -0,0 +1,430 @@
+use rust_json_parser::{
+    AllowedEncodings, AllowedRoots, Dialect, DuplicateKeys, Indent, InvalidUtf8, ParserOptions,
+    PrettyConfig,
+};
+
+pub const USAGE: &str = "\
+Usage:
+  rust-json-parser [FILE]          validate a document
+  rust-json-parser --ndjson [--keep-going] [FILE]
+                                   validate every line as a separate document
+  rust-json-parser fmt [OPTIONS] [FILE]
+                                   pretty-print a document
+  rust-json-parser minify [FILE]   remove insignificant whitespace
+
+Reads from stdin when FILE is missing or '-'.
+
+options:
+  --strict                accept exactly the RFC 8259 grammar
+  --json5                 accept JSON5: comments, trailing commas, unquoted keys...
+  --roots <KIND>          which values the document can be: any, object-or-array
+                          or object (default: any with --strict, object-or-array otherwise)
+  --duplicate-keys <POLICY>
+                          allow, error or warn on keys repeated in an object, or warn
+                          and keep the first-wins or last-wins value when formatting
+                          (default: allow)
+  --max-depth <N>         reject documents with more than N nested objects and arrays
+  --max-string-length <N> reject strings and keys longer than N bytes once unescaped
+  --max-number-length <N> reject numbers longer than N characters
+  --max-size <N>          reject inputs larger than N bytes
+  --max-members <N>       reject objects and arrays with more than N members
+  --invalid-utf8 <POLICY> error on bytes that are not UTF-8, or replace them with U+FFFD
+                          (default: error)
+  --encodings <KIND>      which encodings the input can be in: any, for UTF-8, UTF-16 or
+                          UTF-32, or utf-8 (default: utf-8 with --strict, any otherwise)
+
+fmt options:
+  --check                 exit with status 1 if FILE is not already formatted
+  --indent <N>            indent with N spaces (default: 2)
+  --tabs                  indent with tabs
+  --sort-keys             sort object keys
+  --no-space-after-colon  write \"key\":value instead of \"key\": value
+  --compact-width <N>     keep arrays and objects that fit in N columns on one line
+
+ndjson options:
+  --keep-going            report every invalid line instead of stopping at the first";
+
+#[derive(Debug, PartialEq)]
+pub enum Command {
+    Help,
+    Validate { ndjson: bool, keep_going: bool },
+    Fmt { check: bool, config: PrettyConfig },
+    Minify,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct Args {
+    pub command: Command,
+    pub path: Option<String>,
+    pub options: ParserOptions,
+}
+
+fn value_arg<I: Iterator<Item = String>>(args: &mut I, flag: &str) -> Result<String, String> {
+    args.next()
+        .ok_or_else(|| format!("missing value for {}", flag))
+}
+
+fn number_arg<I: Iterator<Item = String>>(args: &mut I, flag: &str) -> Result<usize, String> {
+    let value = value_arg(args, flag)?;
+    value
+        .parse()
+        .map_err(|_| format!("invalid value for {}: '{}'", flag, value))
+}
+
+pub fn parse_args<I: IntoIterator<Item = String>>(args: I) -> Result<Args, String> {
+    let mut args = args.into_iter().peekable();
+    let mut command = match args.peek().map(String::as_str) {
+        Some("fmt") => {
+            args.next();
+            Command::Fmt {
+                check: false,
+                config: PrettyConfig::default(),
+            }
+        }
+        Some("minify") => {
+            args.next();
+            Command::Minify
+        }
+        _ => Command::Validate {
+            ndjson: false,
+            keep_going: false,
+        },
+    };
+    let mut path = None;
+    let mut options = ParserOptions::new();
+
+    while let Some(arg) = args.next() {
+        match (arg.as_str(), &mut command) {
+            ("-h" | "--help", _) => command = Command::Help,
+            ("--strict", _) => options = options.dialect(Dialect::Strict),
+            ("--json5", _) => options = options.dialect(Dialect::Json5),
+            ("--roots", _) => {
+                let roots = match value_arg(&mut args, "--roots")?.as_str() {
+                    "any" => AllowedRoots::Any,
+                    "object-or-array" => AllowedRoots::ObjectOrArray,
+                    "object" => AllowedRoots::Object,
+                    other => return Err(format!("invalid value for --roots: '{}'", other)),
+                };
+                options = options.allowed_roots(roots)
+            }
+            ("--duplicate-keys", _) => {
+                let policy = match value_arg(&mut args, "--duplicate-keys")?.as_str() {
+                    "allow" => DuplicateKeys::Allow,
+                    "error" => DuplicateKeys::Error,
+                    "warn" => DuplicateKeys::Warn,
+                    "first-wins" => DuplicateKeys::FirstWins,
+                    "last-wins" => DuplicateKeys::LastWins,
+                    other => {
+                        return Err(format!("invalid value for --duplicate-keys: '{}'", other))
+                    }
+                };
+                options = options.duplicate_keys(policy)
+            }
+            ("--max-depth", _) => {
+                options = options.max_depth(number_arg(&mut args, "--max-depth")?)
+            }
+            ("--max-string-length", _) => {
+                options = options.max_string_length(number_arg(&mut args, "--max-string-length")?)
+            }
+            ("--max-number-length", _) => {
+                options = options.max_number_length(number_arg(&mut args, "--max-number-length")?)
+            }
+            ("--max-size", _) => options = options.max_size(number_arg(&mut args, "--max-size")?),
+            ("--max-members", _) => {
+                options = options.max_members(number_arg(&mut args, "--max-members")?)
+            }
+            ("--invalid-utf8", _) => {
+                let policy = match value_arg(&mut args, "--invalid-utf8")?.as_str() {
+                    "error" => InvalidUtf8::Error,
+                    "replace" => InvalidUtf8::Replace,
+                    other => return Err(format!("invalid value for --invalid-utf8: '{}'", other)),
+                };
+                options = options.invalid_utf8(policy)
+            }
+            ("--encodings", _) => {
+                let encodings = match value_arg(&mut args, "--encodings")?.as_str() {
+                    "any" => AllowedEncodings::Any,
+                    "utf-8" => AllowedEncodings::Utf8,
+                    other => return Err(format!("invalid value for --encodings: '{}'", other)),
+                };
+                options = options.allowed_encodings(encodings)
+            }
+            ("--ndjson", Command::Validate { ndjson, .. }) => *ndjson = true,
+            ("--keep-going", Command::Validate { keep_going, .. }) => *keep_going = true,
+            ("--check", Command::Fmt { check, .. }) => *check = true,
+            ("--indent", Command::Fmt { config, .. }) => {
+                config.indent = Indent::Spaces(number_arg(&mut args, "--indent")?)
+            }
+            ("--tabs", Command::Fmt { config, .. }) => config.indent = Indent::Tab,
+            ("--sort-keys", Command::Fmt { config, .. }) => config.sort_keys = true,
+            ("--no-space-after-colon", Command::Fmt { config, .. }) => {
+                config.space_after_colon = false
+            }
+            ("--compact-width", Command::Fmt { config, .. }) => {
+                config.compact_width = Some(number_arg(&mut args, "--compact-width")?)
+            }
+            (_, _) if arg == "-" || !arg.starts_with('-') => {
+                if path.is_some() {
+                    return Err(format!("unexpected argument '{}'", arg));
+                }
+                if arg != "-" {
+                    path = Some(arg);
+                }
+            }
+            (_, _) => return Err(format!("unknown option '{}'", arg)),
+        }
+    }
+
+    if let Command::Validate {
+        ndjson: false,
+        keep_going: true,
+    } = command
+    {
+        return Err("--keep-going requires --ndjson".to_string());
+    }
+
+    Ok(Args {
+        command,
+        path,
+        options,
+    })
+}
+
+#[cfg(test)]
+mod cli_tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Args, String> {
+        parse_args(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn should_validate_stdin_by_default() {
+        assert_eq!(
+            parse(&[]),
+            Ok(Args {
+                command: Command::Validate {
+                    ndjson: false,
+                    keep_going: false
+                },
+                path: None,
+                options: ParserOptions::default()
+            })
+        );
+    }
+
+    #[test]
+    fn should_validate_file() {
+        assert_eq!(
+            parse(&["file.json"]),
+            Ok(Args {
+                command: Command::Validate {
+                    ndjson: false,
+                    keep_going: false
+                },
+                path: Some("file.json".to_string()),
+                options: ParserOptions::default()
+            })
+        );
+    }
+
+    #[test]
+    fn should_read_stdin_for_dash() {
+        assert_eq!(parse(&["fmt", "-"]).unwrap().path, None);
+    }
+
+    #[test]
+    fn should_parse_fmt_options() {
+        assert_eq!(
+            parse(&[
+                "fmt",
+                "--check",
+                "--indent",
+                "4",
+                "--sort-keys",
+                "--no-space-after-colon",
+                "--compact-width",
+                "80",
+                "file.json"
+            ]),
+            Ok(Args {
+                command: Command::Fmt {
+                    check: true,
+                    config: PrettyConfig {
+                        indent: Indent::Spaces(4),
+                        sort_keys: true,
+                        space_after_colon: false,
+                        compact_width: Some(80),
+                    }
+                },
+                path: Some("file.json".to_string()),
+                options: ParserOptions::default()
+            })
+        );
+    }
+
+    #[test]
+    fn should_parse_tabs() {
+        match parse(&["fmt", "--tabs"]).unwrap().command {
+            Command::Fmt { config, .. } => assert_eq!(config.indent, Indent::Tab),
+            command => panic!("unexpected command {:?}", command),
+        }
+    }
+
+    #[test]
+    fn should_reject_fmt_options_when_validating() {
+        assert_eq!(
+            parse(&["--check"]),
+            Err("unknown option '--check'".to_string())
+        );
+    }
+
+    #[test]
+    fn should_reject_invalid_numbers() {
+        assert_eq!(
+            parse(&["fmt", "--indent", "two"]),
+            Err("invalid value for --indent: 'two'".to_string())
+        );
+        assert_eq!(
+            parse(&["fmt", "--compact-width"]),
+            Err("missing value for --compact-width".to_string())
+        );
+    }
+
+    #[test]
+    fn should_reject_several_files() {
+        assert_eq!(
+            parse(&["a.json", "b.json"]),
+            Err("unexpected argument 'b.json'".to_string())
+        );
+    }
+
+    #[test]
+    fn should_parse_minify() {
+        assert_eq!(
+            parse(&["minify", "file.json"]),
+            Ok(Args {
+                command: Command::Minify,
+                path: Some("file.json".to_string()),
+                options: ParserOptions::default()
+            })
+        );
+        assert_eq!(
+            parse(&["minify", "--check"]),
+            Err("unknown option '--check'".to_string())
+        );
+    }
+
+    #[test]
+    fn should_parse_strict_for_every_command() {
+        let strict = ParserOptions::new().dialect(Dialect::Strict);
+        assert_eq!(parse(&["--strict"]).unwrap().options, strict);
+        assert_eq!(
+            parse(&["fmt", "file.json", "--strict"]).unwrap().options,
+            strict
+        );
+        assert_eq!(parse(&["minify", "--strict"]).unwrap().options, strict);
+    }
+
+    #[test]
+    fn should_keep_last_dialect() {
+        assert_eq!(
+            parse(&["--strict", "--json5"]).unwrap().options,
+            ParserOptions::new().dialect(Dialect::Json5)
+        );
+    }
+
+    #[test]
+    fn should_parse_parser_options() {
+        assert_eq!(
+            parse(&[
+                "--roots",
+                "object",
+                "--duplicate-keys",
+                "error",
+                "--max-depth",
+                "10",
+                "--max-string-length",
+                "100",
+                "--max-number-length",
+                "20",
+                "--max-size",
+                "4096",
+                "--max-members",
+                "50",
+                "--invalid-utf8",
+                "replace",
+                "--encodings",
+                "utf-8"
+            ])
+            .unwrap()
+            .options,
+            ParserOptions::new()
+                .allowed_roots(AllowedRoots::Object)
+                .duplicate_keys(DuplicateKeys::Error)
+                .max_depth(10)
+                .max_string_length(100)
+                .max_number_length(20)
+                .max_size(4096)
+                .max_members(50)
+                .invalid_utf8(InvalidUtf8::Replace)
+                .allowed_encodings(AllowedEncodings::Utf8)
+        );
+    }
+
+    #[test]
+    fn should_parse_duplicate_keys_policies() {
+        for (arg, policy) in [
+            ("allow", DuplicateKeys::Allow),
+            ("error", DuplicateKeys::Error),
+            ("warn", DuplicateKeys::Warn),
+            ("first-wins", DuplicateKeys::FirstWins),
+            ("last-wins", DuplicateKeys::LastWins),
+        ] {
+            assert_eq!(
+                parse(&["--duplicate-keys", arg]).unwrap().options,
+                ParserOptions::new().duplicate_keys(policy)
+            );
+        }
+    }
+
+    #[test]
+    fn should_reject_unknown_option_values() {
+        assert_eq!(
+            parse(&["--roots", "scalar"]),
+            Err("invalid value for --roots: 'scalar'".to_string())
+        );
+        assert_eq!(
+            parse(&["--duplicate-keys"]),
+            Err("missing value for --duplicate-keys".to_string())
+        );
+    }
+
+    #[test]
+    fn should_parse_ndjson() {
+        assert_eq!(
+            parse(&["--ndjson", "--keep-going", "log.jsonl"]),
+            Ok(Args {
+                command: Command::Validate {
+                    ndjson: true,
+                    keep_going: true
+                },
+                path: Some("log.jsonl".to_string()),
+                options: ParserOptions::default()
+            })
+        );
+        assert_eq!(
+            parse(&["--keep-going"]),
+            Err("--keep-going requires --ndjson".to_string())
+        );
+        assert_eq!(
+            parse(&["minify", "--ndjson"]),
+            Err("unknown option '--ndjson'".to_string())
+        );
+    }
+
+    #[test]
+    fn should_show_help() {
+        assert_eq!(parse(&["fmt", "--help"]).unwrap().command, Command::Help);
+    }
+}
@@ -0,0 +1,260 @@
+use std::io::{self, BufRead, Read};
+
+use rust_json_parser::{Encoding, JSONError};
+
+const TAIL_CAPACITY: usize = 64 * 1024;
+
+const RED: &str = "\x1b[1;31m";
+const BLUE: &str = "\x1b[1;34m";
+const BOLD: &str = "\x1b[1m";
+const RESET: &str = "\x1b[0m";
+
+// remembers the last bytes consumed from the inner reader, so that the line
+// an error points at can be shown without keeping the whole input around
+pub struct TailReader<R> {
+    inner: R,
+    tail: Vec<u8>,
+    tail_offset: usize,
+    // detected once the first four bytes are read
+    encoding: Option<Encoding>,
+}
+
+impl<R: BufRead> TailReader<R> {
+    pub fn new(inner: R) -> TailReader<R> {
+        TailReader {
+            inner,
+            tail: vec![],
+            tail_offset: 0,
+            encoding: None,
+        }
+    }
+
+    pub fn line_at(&self, offset: usize) -> Option<String> {
+        // lines are shown as UTF-8, in other encodings they would come out garbled
+        // and the caret would not line up with them; a shorter input is all in the tail
+        let encoding = self
+            .encoding
+            .unwrap_or_else(|| Encoding::detect(&self.tail).0);
+        if encoding != Encoding::Utf8 {
+            return None;
+        }
+        if offset < self.tail_offset || offset > self.tail_offset + self.tail.len() {
+            return None;
+        }
+        let idx = offset - self.tail_offset;
+        let start = match self.tail[..idx].iter().rposition(|b| *b == b'\n') {
+            Some(newline) => newline + 1,
+            None if self.tail_offset == 0 => 0,
+            None => return None,
+        };
+        let end = self.tail[idx..]
+            .iter()
+            .position(|b| *b == b'\n')
+            .map_or(self.tail.len(), |newline| idx + newline);
+        Some(String::from_utf8_lossy(&self.tail[start..end]).into_owned())
+    }
+
+    fn record(&mut self, consumed: &[u8]) {
+        self.tail.extend_from_slice(consumed);
+        if self.encoding.is_none() && self.tail_offset == 0 && self.tail.len() >= 4 {
+            self.encoding = Some(Encoding::detect(&self.tail).0);
+        }
+        if self.tail.len() > 2 * TAIL_CAPACITY {
+            let drop = self.tail.len() - TAIL_CAPACITY;
+            self.tail.drain(..drop);
+            self.tail_offset += drop;
+        }
+    }
+}
+
+impl<R: BufRead> Read for TailReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let available = self.fill_buf()?;
+        let amt = available.len().min(buf.len());
+        buf[..amt].copy_from_slice(&available[..amt]);
+        self.consume(amt);
+        Ok(amt)
+    }
+}
+
+impl<R: BufRead> BufRead for TailReader<R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        if let Ok(buf) = self.inner.fill_buf() {
+            let consumed = buf[..amt.min(buf.len())].to_vec();
+            self.record(&consumed);
+        }
+        self.inner.consume(amt);
+    }
+}
+
+fn paint(text: &str, style: &str, color: bool) -> String {
+    if color {
+        format!("{}{}{}", style, text, RESET)
+    } else {
+        text.to_string()
+    }
+}
+
+pub fn render(
+    error: &JSONError,
+    file_name: &str,
+    source_line: Option<&str>,
+    color: bool,
+) -> String {
+    let line_number = error.line().to_string();
+    let gutter = " ".repeat(line_number.len());
+    let mut out = format!(
+        "{}{}\n{}{} {}:{}:{}\n",
+        paint("error", RED, color),
+        paint(&format!(": {}", error.kind()), BOLD, color),
+        gutter,
+        paint("-->", BLUE, color),
+        file_name,
+        error.line(),
+        error.column()
+    );
+
+    if let Some(source_line) = source_line {
+        let span = error.span();
+        let before: String = source_line
+            .chars()
+            .take(error.column() - 1)
+            .map(|c| if c == '\t' { '\t' } else { ' ' })
+            .collect();
+        let width = if span.end.line == span.start.line {
+            span.end.column.saturating_sub(span.start.column).max(1)
+        } else {
+            1
+        };
+        let bar = paint("|", BLUE, color);
+        out.push_str(&format!("{} {}\n", gutter, bar));
+        out.push_str(&format!(
+            "{} {} {}\n",
+            paint(&line_number, BLUE, color),
+            bar,
+            source_line
+        ));
+        out.push_str(&format!(
+            "{} {} {}{}\n",
+            gutter,
+            bar,
+            before,
+            paint(&"^".repeat(width), RED, color)
+        ));
+    }
+    out
+}
+
+#[cfg(test)]
+mod diagnostic_tests {
+    use std::io::BufRead;
+
+    use super::*;
+    use rust_json_parser::check_valid;
+
+    fn render_input(input: &str) -> String {
+        let mut reader = TailReader::new(input.as_bytes());
+        let err = check_valid(&mut reader).unwrap_err();
+        render(
+            &err,
+            "test.json",
+            reader.line_at(err.offset()).as_deref(),
+            false,
+        )
+    }
+
+    #[test]
+    fn should_render_snippet_with_caret_under_token() {
+        assert_eq!(
+            render_input("{\n  \"key\": }\n"),
+            "error: Unexpected '}', expected a value after ':'\n \
+             --> test.json:2:10\n  \
+             |\n\
+             2 |   \"key\": }\n  \
+             |          ^\n"
+        );
+    }
+
+    #[test]
+    fn should_underline_whole_token() {
+        assert_eq!(
+            render_input("[1 \"abc\"]"),
+            "error: Unexpected '<string literal>', expected ',' or ']'\n \
+             --> test.json:1:4\n  \
+             |\n\
+             1 | [1 \"abc\"]\n  \
+             |    ^^^^^\n"
+        );
+    }
+
+    #[test]
+    fn should_render_snippet_for_lexer_errors() {
+        assert_eq!(
+            render_input("[1, @]"),
+            "error: Unexpected '@'\n \
+             --> test.json:1:5\n  \
+             |\n\
+             1 | [1, @]\n  \
+             |     ^\n"
+        );
+    }
+
+    #[test]
+    fn should_keep_tabs_when_padding_caret() {
+        let err = check_valid("[1".as_bytes()).unwrap_err();
+        let rendered = render(&err, "test.json", Some("\t\tx"), false);
+        assert!(rendered.ends_with("1 | \t\tx\n  | \t\t^\n"), "{}", rendered);
+    }
+
+    #[test]
+    fn should_widen_gutter_for_long_line_numbers() {
+        let input = format!("[{}1 2]", "\n".repeat(10));
+        assert!(render_input(&input).contains("  --> test.json:11:3\n   |\n11 | 1 2]\n   |   ^\n"));
+    }
+
+    #[test]
+    fn should_render_without_snippet_when_line_is_unknown() {
+        let err = check_valid("[".as_bytes()).unwrap_err();
+        assert_eq!(
+            render(&err, "<stdin>", None, false),
+            "error: Unexpected EOF\n --> <stdin>:1:2\n"
+        );
+    }
+
+    #[test]
+    fn should_colorize_when_asked() {
+        let err = check_valid("[".as_bytes()).unwrap_err();
+        let rendered = render(&err, "<stdin>", Some("["), true);
+        assert!(rendered.starts_with("\x1b[1;31merror\x1b[0m"));
+        assert!(rendered.contains("\x1b[1;31m^\x1b[0m"));
+    }
+
+    #[test]
+    fn should_render_without_snippet_for_other_encodings() {
+        let utf16: Vec<u8> = "{\"a\": }".bytes().flat_map(|b| [b, 0]).collect();
+        let mut reader = TailReader::new(&utf16[..]);
+        let err = check_valid(&mut reader).unwrap_err();
+        assert_eq!(reader.line_at(err.offset()), None);
+        let mut reader = TailReader::new("\u{FEFF}[1 2]".as_bytes());
+        let err = check_valid(&mut reader).unwrap_err();
+        assert_eq!(
+            reader.line_at(err.offset()).as_deref(),
+            Some("\u{FEFF}[1 2]")
+        );
+    }
+
+    #[test]
+    fn should_forget_lines_far_behind() {
+        let input = format!("{}\n[", " ".repeat(3 * TAIL_CAPACITY));
+        let mut reader = TailReader::new(input.as_bytes());
+        let mut sink = vec![];
+        reader.read_until(b'\n', &mut sink).unwrap();
+        reader.read_until(b'\n', &mut sink).unwrap();
+        assert_eq!(reader.line_at(0), None);
+        assert_eq!(reader.line_at(3 * TAIL_CAPACITY + 1).as_deref(), Some("["));
+    }
+}
@@ -0,0 +1,20 @@
+mod parser;
+mod serializer;
+
+pub use parser::{
+    check_ndjson, check_valid, check_valid_with, lex, minify, minify_with, minify_with_visitor,
+    parse_documents, parse_events, parse_value, parse_value_with, parse_value_with_visitor, visit,
+    AllowedEncodings, AllowedRoots, Dialect, Documents, DuplicateKeys, Encoding, Events,
+    InvalidUtf8, IoError, JSONError, JSONErrorKind, JsonEvent, JsonNumber, JsonValue, JsonVisitor,
+    Lexer, NdjsonLines, ParserOptions, Position, PushParser, Span, SpannedToken, Token,
+    ValueBuilder, DEFAULT_VALUE_DEPTH,
+};
+#[cfg(feature = "arbitrary_precision")]
+pub use bigdecimal::BigDecimal;
+#[cfg(feature = "arbitrary_precision")]
+pub use num_bigint::BigInt;
+
+pub use serializer::{
+    to_string, to_string_pretty, to_writer, to_writer_pretty, tokens_to_writer, Indent,
+    PrettyConfig,
+};
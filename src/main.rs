@@ -1,17 +1,173 @@
-use std::{io::BufReader, process::exit};
+use std::{
+    env,
+    fs::File,
+    io::{self, BufRead, BufReader, BufWriter, IsTerminal, Write},
+    process::exit,
+};
 
-mod parser;
+use rust_json_parser::{
+    check_ndjson, minify_with_visitor, parse_value_with_visitor, to_string_pretty, visit,
+    JSONError, JsonVisitor, ParserOptions, PrettyConfig, Span,
+};
 
-fn main() {
-    let buf = BufReader::new(std::io::stdin());
+mod cli;
+use cli::{parse_args, Command, USAGE};
+
+mod diagnostic;
+use diagnostic::{render, TailReader};
+
+fn use_color() -> bool {
+    env::var_os("NO_COLOR").is_none() && io::stderr().is_terminal()
+}
+
+fn report<R: BufRead>(error: &JSONError, reader: &TailReader<R>, file_name: &str) {
+    let source_line = reader.line_at(error.offset());
+    eprint!(
+        "{}",
+        render(error, file_name, source_line.as_deref(), use_color())
+    );
+}
+
+// only told about repeated keys with the warn, first-wins and last-wins policies
+struct DuplicateKeyWarnings<'a> {
+    file_name: &'a str,
+}
 
-    match parser::check_valid(buf) {
+impl JsonVisitor for DuplicateKeyWarnings<'_> {
+    fn duplicate_key(&mut self, key: &str, span: Span) {
+        eprintln!(
+            "warning: duplicate key \"{}\" at {}:{}:{}",
+            key, self.file_name, span.start.line, span.start.column
+        );
+    }
+}
+
+fn validate<R: BufRead>(reader: R, file_name: &str, options: &ParserOptions) -> bool {
+    let mut reader = TailReader::new(reader);
+    let mut warnings = DuplicateKeyWarnings { file_name };
+    match visit(&mut reader, options, &mut warnings) {
         Err(e) => {
-            eprintln!("{}", e);
-            exit(1);
+            report(&e, &reader, file_name);
+            false
         }
         Ok(()) => {
-            println!("ok")
+            println!("ok");
+            true
+        }
+    }
+}
+
+fn validate_ndjson<R: BufRead>(
+    reader: R,
+    file_name: &str,
+    options: &ParserOptions,
+    keep_going: bool,
+) -> bool {
+    let mut lines = check_ndjson(TailReader::new(reader), options)
+        .with_visitor(DuplicateKeyWarnings { file_name });
+    let (mut total, mut invalid) = (0, 0);
+    while let Some(result) = lines.next() {
+        total += 1;
+        if let Err(e) = result {
+            invalid += 1;
+            report(&e, lines.get_ref(), file_name);
+            if !keep_going {
+                return false;
+            }
+        }
+    }
+    if invalid > 0 {
+        eprintln!("error: {} of {} lines are invalid", invalid, total);
+        return false;
+    }
+    println!("ok");
+    true
+}
+
+fn format<R: BufRead>(
+    mut reader: R,
+    file_name: &str,
+    options: &ParserOptions,
+    check: bool,
+    config: &PrettyConfig,
+) -> bool {
+    let mut input = Vec::new();
+    if let Err(e) = reader.read_to_end(&mut input) {
+        eprintln!("error: cannot read {}: {}", file_name, e);
+        return false;
+    }
+    let mut reader = TailReader::new(&input[..]);
+    let mut warnings = DuplicateKeyWarnings { file_name };
+    let value = match parse_value_with_visitor(&mut reader, options, &mut warnings) {
+        Ok(value) => value,
+        Err(e) => {
+            report(&e, &reader, file_name);
+            return false;
+        }
+    };
+
+    let mut formatted = to_string_pretty(&value, config);
+    formatted.push('\n');
+    if check {
+        let formatted_already = formatted.as_bytes() == input;
+        if !formatted_already {
+            eprintln!("{} is not formatted", file_name);
+        }
+        formatted_already
+    } else {
+        io::stdout().write_all(formatted.as_bytes()).is_ok()
+    }
+}
+
+fn minify_to_stdout<R: BufRead>(reader: R, file_name: &str, options: &ParserOptions) -> bool {
+    let mut reader = TailReader::new(reader);
+    let mut writer = BufWriter::new(io::stdout().lock());
+    let mut warnings = DuplicateKeyWarnings { file_name };
+    if let Err(e) = minify_with_visitor(&mut reader, &mut writer, options, &mut warnings) {
+        report(&e, &reader, file_name);
+        return false;
+    }
+    if let Err(e) = writeln!(writer).and_then(|()| writer.flush()) {
+        eprintln!("error: cannot write output: {}", e);
+        return false;
+    }
+    true
+}
+
+fn main() {
+    let args = match parse_args(env::args().skip(1)) {
+        Ok(args) => args,
+        Err(e) => {
+            eprintln!("error: {}\n\n{}", e, USAGE);
+            exit(2);
+        }
+    };
+
+    let (reader, file_name): (Box<dyn BufRead>, String) = match args.path {
+        Some(path) => match File::open(&path) {
+            Ok(file) => (Box::new(BufReader::new(file)), path),
+            Err(e) => {
+                eprintln!("error: cannot open {}: {}", path, e);
+                exit(1);
+            }
+        },
+        None => (Box::new(BufReader::new(io::stdin())), "<stdin>".to_string()),
+    };
+
+    let success = match args.command {
+        Command::Help => {
+            println!("{}", USAGE);
+            true
         }
+        Command::Validate { ndjson: false, .. } => validate(reader, &file_name, &args.options),
+        Command::Validate {
+            ndjson: true,
+            keep_going,
+        } => validate_ndjson(reader, &file_name, &args.options, keep_going),
+        Command::Fmt { check, config } => format(reader, &file_name, &args.options, check, &config),
+        Command::Minify => minify_to_stdout(reader, &file_name, &args.options),
+    };
+    if !success {
+        exit(1);
     }
 }
@@ -0,0 +1,147 @@
+// RFC 8259 conformance of the strict dialect, cases modeled after JSONTestSuite
+use crate::parser::{
+    check_valid_with,
+    options::{Dialect, ParserOptions},
+};
+
+macro_rules! test_strict_accepts {
+    ($($name:ident: $value:expr,)*) => {
+        $(
+            #[test]
+            fn $name() {
+                let input: &[u8] = $value.as_ref();
+                assert_eq!(Ok(()), check_valid_with(input, &ParserOptions::new().dialect(Dialect::Strict)));
+            }
+        )*
+    }
+}
+
+macro_rules! test_strict_rejects {
+    ($($name:ident: $value:expr,)*) => {
+        $(
+            #[test]
+            fn $name() {
+                let input: &[u8] = $value.as_ref();
+                assert!(check_valid_with(input, &ParserOptions::new().dialect(Dialect::Strict)).is_err());
+            }
+        )*
+    }
+}
+
+mod accepts {
+    use super::*;
+
+    test_strict_accepts! {
+        empty_array: "[]",
+        empty_object: "{}",
+        nested_arrays: "[[[[[]]]]]",
+        array_of_all_values: "[null, true, false, 0, \"\", [], {}]",
+        object_with_members: "{\"a\": 1, \"b\": [true, false, null], \"c\": {\"d\": \"e\"}}",
+        object_with_empty_key: "{\"\": 0}",
+        object_with_duplicate_keys: "{\"a\": 1, \"a\": 2}",
+
+        string_root: "\"abc\"",
+        number_root: "42",
+        negative_number_root: "-1.5e3",
+        true_root: "true",
+        false_root: "false",
+        null_root: "null",
+
+        space_around_root: " [1] ",
+        tab_as_whitespace: "[1,\t2]",
+        carriage_return_as_whitespace: "[1,\r2]",
+        crlf_line_endings: "{\r\n  \"a\": 1\r\n}\r\n",
+        whitespace_after_number_root: "1\t\r\n ",
+        new_lines_before_root: "\n\n[]",
+
+        zero: "[0]",
+        negative_zero: "[-0]",
+        negative_integer: "[-123]",
+        fraction: "[1.5]",
+        zero_fraction: "[0.25]",
+        exponent: "[1e10]",
+        capital_exponent: "[1E10]",
+        exponent_with_plus: "[1e+2]",
+        exponent_with_minus: "[1e-2]",
+        exponent_after_fraction: "[-0.5e-3]",
+        exponent_with_leading_zeros: "[1e007]",
+        long_integer: "[123456789012345678901234567890]",
+
+        empty_string: "[\"\"]",
+        all_short_escapes: "[\"\\\"\\\\\\/\\b\\f\\n\\r\\t\"]",
+        unicode_escape: "[\"\\u00e9\"]",
+        escaped_nul: "[\"\\u0000\"]",
+        surrogate_pair: "[\"\\uD834\\uDD1E\"]",
+        unescaped_slash: "[\"a/b\"]",
+        unescaped_non_ascii: "[\"é😀\"]",
+        unescaped_del: "[\"\u{7F}\"]",
+        unescaped_line_separator: "[\"\u{2028}\"]",
+    }
+}
+
+mod rejects {
+    use super::*;
+
+    test_strict_rejects! {
+        empty_document: "",
+        only_whitespace: " \n\t",
+        two_roots: "[1] [2]",
+        two_scalar_roots: "1 2",
+        extra_closing_bracket: "[1]]",
+        extra_closing_brace: "{\"a\": 1}}",
+        unclosed_array: "[1",
+        unclosed_object: "{\"a\": 1",
+        unclosed_string: "\"abc",
+
+        missing_comma: "[1 2]",
+        trailing_comma_in_array: "[1,]",
+        trailing_comma_in_object: "{\"a\": 1,}",
+        leading_comma: "[,1]",
+        missing_colon: "{\"a\" 1}",
+        missing_value: "{\"a\":}",
+        unquoted_key: "{a: 1}",
+        number_key: "{1: 1}",
+        single_quotes: "['a']",
+        adjacent_strings: "[\"a\" \"b\"]",
+        comment: "/* c */ []",
+        line_comment: "[] // c",
+
+        plus_sign: "[+1]",
+        plus_sign_root: "+1",
+        leading_zero: "[01]",
+        negative_leading_zero: "[-01]",
+        trailing_point: "[1.]",
+        leading_point: "[.5]",
+        lone_minus: "[-]",
+        empty_exponent: "[1e]",
+        empty_signed_exponent: "[1e+]",
+        fractional_exponent: "[1e1.5]",
+        hex_number: "[0x10]",
+        infinity: "[Infinity]",
+        nan: "[NaN]",
+
+        capitalized_true: "[True]",
+        truncated_null: "[nul]",
+        truncated_true_root: "tru",
+
+        unescaped_tab: "[\"\t\"]",
+        unescaped_new_line: "[\"a\nb\"]",
+        unescaped_nul: "[\"\u{0}\"]",
+        unescaped_control_character: "[\"\u{1F}\"]",
+        invalid_escape: "[\"\\x\"]",
+        short_unicode_escape: "[\"\\u12\"]",
+        non_hex_unicode_escape: "[\"\\u12G4\"]",
+
+        form_feed_as_whitespace: "[\u{0C}1]",
+        vertical_tab_as_whitespace: "[\u{0B}1]",
+        no_break_space_as_whitespace: "[\u{A0}1]",
+
+        invalid_utf8: b"[\"\xff\"]",
+        truncated_utf8: b"[\"\xc3\"]",
+        overlong_utf8: b"[\"\xc0\xaf\"]",
+
+        // the RFC leaves lone surrogates to the implementation, this one rejects them
+        lone_high_surrogate: "[\"\\uD834\"]",
+        lone_low_surrogate: "[\"\\uDD1E\"]",
+    }
+}
@@ -0,0 +1,251 @@
+use std::io::BufRead;
+
+use super::{
+    error::{JSONError, JSONErrorKind},
+    lexer::Lexer,
+    options::ParserOptions,
+    parser::State,
+    token::{Span, Token},
+    value::{JsonValue, ValueBuilder},
+};
+
+// reads a stream of documents written back to back (`{}{}[1]`), possibly
+// separated by whitespace or by the RFC 7464 record separator (0x1E), yielding
+// every value with the span it covers in the input
+pub struct Documents<R> {
+    tokens: Lexer<R>,
+    options: ParserOptions,
+    // after an invalid document, the rest of it is skipped up to the next
+    // record separator, without one the stream cannot be read any further
+    recovering: bool,
+    done: bool,
+}
+
+impl<R: BufRead> Documents<R> {
+    fn skip_to_separator(&mut self) {
+        for token in self.tokens.by_ref() {
+            match token {
+                Ok(token) if token.token == Token::RecordSeparator => {
+                    self.recovering = false;
+                    return;
+                }
+                Ok(_) => {}
+                Err(_) => break,
+            }
+        }
+        self.done = true;
+    }
+}
+
+impl<R: BufRead> Iterator for Documents<R> {
+    type Item = Result<(JsonValue, Span), JSONError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.recovering {
+            self.skip_to_separator();
+        }
+        if self.done {
+            return None;
+        }
+
+        let mut state = State::new(&self.options);
+        let mut builder = ValueBuilder::with_options(&self.options);
+        let mut span: Option<Span> = None;
+        loop {
+            let token = match self.tokens.next() {
+                Some(Ok(token)) => token,
+                Some(Err(err)) => {
+                    self.done = true;
+                    return Some(Err(err));
+                }
+                None => {
+                    self.done = true;
+                    // only whitespace was left after the last document
+                    let end = span?.end;
+                    return Some(Err(JSONError::new(
+                        JSONErrorKind::UnexpectedEof,
+                        Span::at(end),
+                    )));
+                }
+            };
+            match (&token.token, span.as_mut()) {
+                (Token::NewLine, _) | (Token::RecordSeparator, None) => continue,
+                (_, None) => span = Some(token.span),
+                (_, Some(span)) => span.end = token.span.end,
+            }
+            let separator = token.token == Token::RecordSeparator;
+            if let Err(err) = state.step(token, &mut builder) {
+                // the separator that cut the document short starts the next one
+                self.recovering = !separator;
+                return Some(Err(err));
+            }
+            if state.is_done() {
+                let value = builder
+                    .finish()
+                    .expect("a complete document always produces a root value");
+                return Some(Ok((value, span.unwrap())));
+            }
+        }
+    }
+}
+
+pub fn parse_documents<R: BufRead>(reader: R, options: &ParserOptions) -> Documents<R> {
+    Documents {
+        tokens: Lexer::with_options(reader, options).with_record_separators(),
+        options: options.for_values(),
+        recovering: false,
+        done: false,
+    }
+}
+
+#[cfg(test)]
+mod documents_tests {
+    use crate::parser::{
+        parse_documents, AllowedRoots, Dialect, JSONError, JsonNumber, JsonValue, ParserOptions,
+        Span,
+    };
+
+    fn any_root() -> ParserOptions {
+        ParserOptions::new().allowed_roots(AllowedRoots::Any)
+    }
+
+    fn documents(input: &str, options: &ParserOptions) -> Vec<Result<JsonValue, String>> {
+        parse_documents(input.as_bytes(), options)
+            .map(|document| {
+                document
+                    .map(|(value, _)| value)
+                    .map_err(|err| err.to_string())
+            })
+            .collect()
+    }
+
+    fn ranges(input: &str) -> Vec<(usize, usize)> {
+        parse_documents(input.as_bytes(), &any_root())
+            .map(|document| {
+                let (_, span) = document.unwrap();
+                (span.start.offset, span.end.offset)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn should_read_concatenated_documents() {
+        assert_eq!(
+            documents("{}{}[1]", &ParserOptions::default()),
+            vec![
+                Ok(JsonValue::Object(vec![])),
+                Ok(JsonValue::Object(vec![])),
+                Ok(JsonValue::Array(vec![JsonValue::Number(JsonNumber::from(
+                    1.
+                ))])),
+            ]
+        );
+        assert_eq!(ranges("{}{}[1]"), vec![(0, 2), (2, 4), (4, 7)]);
+    }
+
+    #[test]
+    fn should_read_documents_separated_by_whitespace() {
+        assert_eq!(
+            documents("1 \"a\"\ntrue\n\nnull\n", &any_root()),
+            vec![
+                Ok(JsonValue::Number(JsonNumber::from(1.))),
+                Ok(JsonValue::String("a".to_string())),
+                Ok(JsonValue::Bool(true)),
+                Ok(JsonValue::Null),
+            ]
+        );
+        assert_eq!(ranges(" [1, 2]\n {\"a\": 3} "), vec![(1, 7), (9, 17)]);
+    }
+
+    #[test]
+    fn should_read_json_text_sequences() {
+        let input = "\u{1E}{\"a\": 1}\n\u{1E}[]\n\u{1E}42\n";
+        assert_eq!(
+            documents(input, &any_root()),
+            vec![
+                Ok(JsonValue::Object(vec![(
+                    "a".to_string(),
+                    JsonValue::Number(JsonNumber::from(1.))
+                )])),
+                Ok(JsonValue::Array(vec![])),
+                Ok(JsonValue::Number(JsonNumber::from(42.))),
+            ]
+        );
+        assert_eq!(ranges(input), vec![(1, 9), (11, 13), (15, 17)]);
+    }
+
+    #[test]
+    fn should_read_nothing_from_empty_stream() {
+        assert_eq!(documents("", &ParserOptions::default()), vec![]);
+        assert_eq!(documents(" \n\u{1E}\n", &ParserOptions::default()), vec![]);
+    }
+
+    #[test]
+    fn should_report_truncated_last_document() {
+        assert_eq!(
+            documents("{}[1,", &ParserOptions::default()),
+            vec![
+                Ok(JsonValue::Object(vec![])),
+                Err("Unexpected EOF: at line 1, column 6".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn should_continue_after_invalid_sequence_element() {
+        assert_eq!(
+            documents("\u{1E}[1,\n\u{1E}{\"a\" 1}\n\u{1E}2\n", &any_root()),
+            vec![
+                Err(
+                    "Unexpected '<record separator>', expected a value after ',': at line 2, column 1"
+                        .to_string()
+                ),
+                Err("Unexpected '<number>', expected ':' after the key: at line 2, column 7"
+                    .to_string()),
+                Ok(JsonValue::Number(JsonNumber::from(2.))),
+            ]
+        );
+    }
+
+    #[test]
+    fn should_stop_after_invalid_concatenated_document() {
+        assert_eq!(
+            documents("{}}{}", &ParserOptions::default()),
+            vec![
+                Ok(JsonValue::Object(vec![])),
+                Err("Unexpected '}', expected '{' or '[': at line 1, column 3".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn should_stop_at_lexer_errors() {
+        let results: Vec<Result<(JsonValue, Span), JSONError>> =
+            parse_documents("[] @ []".as_bytes(), &ParserOptions::default()).collect();
+        assert_eq!(results.len(), 2);
+        assert!(results[1].is_err());
+    }
+
+    #[test]
+    fn should_use_parser_options() {
+        let options = ParserOptions::new().dialect(Dialect::Json5);
+        assert_eq!(
+            documents("{a: 1,} // first\n[2,]", &options),
+            vec![
+                Ok(JsonValue::Object(vec![(
+                    "a".to_string(),
+                    JsonValue::Number(JsonNumber::from(1.))
+                )])),
+                Ok(JsonValue::Array(vec![JsonValue::Number(JsonNumber::from(
+                    2.
+                ))])),
+            ]
+        );
+        assert_eq!(
+            documents("1 2", &ParserOptions::default()),
+            vec![Err(
+                "Unexpected '<number>', expected '{' or '[': at line 1, column 1".to_string()
+            )]
+        );
+    }
+}
@@ -0,0 +1,74 @@
+use core::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    Utf8,
+    Utf16Le,
+    Utf16Be,
+    Utf32Le,
+    Utf32Be,
+}
+
+impl Encoding {
+    // from the byte order mark if there is one, otherwise from the null bytes
+    // among the first four, as the first two characters of a document are ASCII
+    // (RFC 4627, section 3); returns the length of the byte order mark too
+    pub fn detect(start: &[u8]) -> (Encoding, usize) {
+        match start {
+            [0xEF, 0xBB, 0xBF, ..] => (Encoding::Utf8, 3),
+            [0x00, 0x00, 0xFE, 0xFF, ..] => (Encoding::Utf32Be, 4),
+            [0xFF, 0xFE, 0x00, 0x00, ..] => (Encoding::Utf32Le, 4),
+            [0xFE, 0xFF, ..] => (Encoding::Utf16Be, 2),
+            [0xFF, 0xFE, ..] => (Encoding::Utf16Le, 2),
+            [0, 0, 0, _, ..] => (Encoding::Utf32Be, 0),
+            [_, 0, 0, 0, ..] => (Encoding::Utf32Le, 0),
+            [0, _, ..] => (Encoding::Utf16Be, 0),
+            [_, 0, ..] => (Encoding::Utf16Le, 0),
+            _ => (Encoding::Utf8, 0),
+        }
+    }
+}
+
+impl fmt::Display for Encoding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Encoding::Utf8 => "UTF-8",
+            Encoding::Utf16Le => "UTF-16LE",
+            Encoding::Utf16Be => "UTF-16BE",
+            Encoding::Utf32Le => "UTF-32LE",
+            Encoding::Utf32Be => "UTF-32BE",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[cfg(test)]
+mod encoding_tests {
+    use super::Encoding;
+
+    #[test]
+    fn should_detect_byte_order_marks() {
+        assert_eq!((Encoding::Utf8, 3), Encoding::detect(b"\xEF\xBB\xBF{}"));
+        assert_eq!((Encoding::Utf16Be, 2), Encoding::detect(b"\xFE\xFF\x00{"));
+        assert_eq!((Encoding::Utf16Le, 2), Encoding::detect(b"\xFF\xFE{\x00"));
+        assert_eq!(
+            (Encoding::Utf32Be, 4),
+            Encoding::detect(b"\x00\x00\xFE\xFF")
+        );
+        assert_eq!(
+            (Encoding::Utf32Le, 4),
+            Encoding::detect(b"\xFF\xFE\x00\x00")
+        );
+    }
+
+    #[test]
+    fn should_detect_null_byte_patterns() {
+        assert_eq!((Encoding::Utf32Be, 0), Encoding::detect(b"\x00\x00\x00["));
+        assert_eq!((Encoding::Utf32Le, 0), Encoding::detect(b"[\x00\x00\x00"));
+        assert_eq!((Encoding::Utf16Be, 0), Encoding::detect(b"\x00[\x00]"));
+        assert_eq!((Encoding::Utf16Le, 0), Encoding::detect(b"[\x00]\x00"));
+        assert_eq!((Encoding::Utf16Le, 0), Encoding::detect(b"1\x00"));
+        assert_eq!((Encoding::Utf8, 0), Encoding::detect(b"[]"));
+        assert_eq!((Encoding::Utf8, 0), Encoding::detect(b""));
+    }
+}
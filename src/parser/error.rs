@@ -1,19 +1,183 @@
 use core::fmt;
+use std::{error::Error, io, sync::Arc};
 
-#[derive(Debug, Clone, Eq, PartialEq)]
+use super::{
+    encoding::Encoding,
+    token::{Span, Token},
+};
+
+#[derive(Debug, Clone)]
+pub struct IoError(Arc<io::Error>);
+
+impl IoError {
+    pub fn get_ref(&self) -> &io::Error {
+        &self.0
+    }
+}
+
+impl From<io::Error> for IoError {
+    fn from(err: io::Error) -> IoError {
+        IoError(Arc::new(err))
+    }
+}
+
+impl PartialEq for IoError {
+    fn eq(&self, other: &IoError) -> bool {
+        self.0.kind() == other.0.kind()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum JSONErrorKind {
+    UnexpectedChar(char),
+    UnexpectedToken {
+        found: Token,
+        expected: Option<&'static str>,
+    },
+    UnexpectedEof,
+    ControlCharacter(char),
+    InvalidEscape(char),
+    UnpairedSurrogate(u16),
+    InvalidNumber,
+    InvalidUtf8,
+    InvalidEncoding(Encoding),
+    UnsupportedEncoding(Encoding),
+    DuplicateKey(String),
+    DepthLimitExceeded(usize),
+    StringLengthLimitExceeded(usize),
+    NumberLengthLimitExceeded(usize),
+    SizeLimitExceeded(usize),
+    MemberLimitExceeded(usize),
+    Io(IoError),
+}
+
+impl fmt::Display for JSONErrorKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            JSONErrorKind::UnexpectedChar(c) => write!(f, "Unexpected '{}'", c),
+            JSONErrorKind::UnexpectedToken { found, expected } => {
+                write!(f, "Unexpected {}", found)?;
+                if let Some(expected) = expected {
+                    write!(f, ", expected {}", expected)?;
+                }
+                Ok(())
+            }
+            JSONErrorKind::UnexpectedEof => write!(f, "Unexpected EOF"),
+            JSONErrorKind::ControlCharacter('\t') => write!(f, "Unexpected <tab>"),
+            JSONErrorKind::ControlCharacter(c) => {
+                write!(f, "Unexpected control character U+{:04X}", *c as u32)
+            }
+            JSONErrorKind::InvalidEscape(c) => write!(f, "Invalid escape character '{}'", c),
+            JSONErrorKind::UnpairedSurrogate(code) => {
+                write!(f, "Unpaired surrogate '\\u{:04x}'", code)
+            }
+            JSONErrorKind::InvalidNumber => write!(f, "Invalid number"),
+            JSONErrorKind::InvalidUtf8 => write!(f, "Invalid UTF-8"),
+            JSONErrorKind::InvalidEncoding(encoding) => write!(f, "Invalid {}", encoding),
+            JSONErrorKind::UnsupportedEncoding(encoding) => {
+                write!(f, "Unsupported encoding {}, expected UTF-8", encoding)
+            }
+            JSONErrorKind::DuplicateKey(key) => write!(f, "Duplicate key \"{}\"", key),
+            JSONErrorKind::DepthLimitExceeded(limit) => {
+                write!(f, "Nesting deeper than {} levels", limit)
+            }
+            JSONErrorKind::StringLengthLimitExceeded(limit) => {
+                write!(f, "String longer than {} bytes", limit)
+            }
+            JSONErrorKind::NumberLengthLimitExceeded(limit) => {
+                write!(f, "Number longer than {} characters", limit)
+            }
+            JSONErrorKind::SizeLimitExceeded(limit) => {
+                write!(f, "Input larger than {} bytes", limit)
+            }
+            JSONErrorKind::MemberLimitExceeded(limit) => {
+                write!(f, "Object or array with more than {} members", limit)
+            }
+            JSONErrorKind::Io(err) => write!(f, "{}", err.get_ref()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct JSONError {
-    message: String,
-    line: i64,
+    kind: JSONErrorKind,
+    span: Span,
 }
 
 impl fmt::Display for JSONError {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}: at line {}", self.message, self.line)
+        write!(
+            f,
+            "{}: at line {}, column {}",
+            self.kind,
+            self.line(),
+            self.column()
+        )
+    }
+}
+
+impl Error for JSONError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match &self.kind {
+            JSONErrorKind::Io(err) => Some(err.get_ref()),
+            _ => None,
+        }
     }
 }
 
 impl JSONError {
-    pub fn new(message: String, line: i64) -> JSONError {
-        JSONError { message, line }
+    pub fn new(kind: JSONErrorKind, span: Span) -> JSONError {
+        JSONError { kind, span }
+    }
+
+    pub fn kind(&self) -> &JSONErrorKind {
+        &self.kind
+    }
+
+    pub fn span(&self) -> Span {
+        self.span
+    }
+
+    pub fn line(&self) -> usize {
+        self.span.start.line
+    }
+
+    pub fn column(&self) -> usize {
+        self.span.start.column
+    }
+
+    pub fn offset(&self) -> usize {
+        self.span.start.offset
+    }
+}
+
+#[cfg(test)]
+mod error_tests {
+    use super::*;
+    use crate::parser::token::Position;
+
+    #[test]
+    fn should_expose_io_error_as_source() {
+        let err = JSONError::new(
+            JSONErrorKind::Io(io::Error::other("broken pipe").into()),
+            Span::at(Position::start()),
+        );
+        assert_eq!("broken pipe", err.source().unwrap().to_string());
+        assert_eq!("broken pipe: at line 1, column 1", err.to_string());
+    }
+
+    #[test]
+    fn should_not_have_source_for_syntax_errors() {
+        let err = JSONError::new(JSONErrorKind::UnexpectedEof, Span::at(Position::start()));
+        assert!(err.source().is_none());
+    }
+
+    #[test]
+    fn should_render_expected_tokens() {
+        let kind = JSONErrorKind::UnexpectedToken {
+            found: Token::ClosedBracket,
+            expected: Some("a value"),
+        };
+        assert_eq!("Unexpected ']', expected a value", kind.to_string());
     }
 }
@@ -0,0 +1,323 @@
+use std::{collections::VecDeque, io::BufRead};
+
+use super::{
+    error::JSONError,
+    lexer::Lexer,
+    options::ParserOptions,
+    parser::{parse_with, State},
+    token::{Position, Span},
+    value::JsonValue,
+};
+
+// told about the document as the parser goes through it, without a tree ever
+// being built; every method does nothing unless overridden
+pub trait JsonVisitor {
+    fn start_object(&mut self) {}
+    // told right before `key` when the object already has that key, unless
+    // duplicated keys are allowed or an error
+    fn duplicate_key(&mut self, _key: &str, _span: Span) {}
+    fn key(&mut self, _key: String) {}
+    fn start_array(&mut self) {}
+    // a string, number, boolean or null
+    fn value(&mut self, _value: JsonValue) {}
+    fn end_array(&mut self) {}
+    fn end_object(&mut self) {}
+}
+
+impl JsonVisitor for () {}
+
+impl<V: JsonVisitor + ?Sized> JsonVisitor for &mut V {
+    fn start_object(&mut self) {
+        (**self).start_object();
+    }
+
+    fn duplicate_key(&mut self, key: &str, span: Span) {
+        (**self).duplicate_key(key, span);
+    }
+
+    fn key(&mut self, key: String) {
+        (**self).key(key);
+    }
+
+    fn start_array(&mut self) {
+        (**self).start_array();
+    }
+
+    fn value(&mut self, value: JsonValue) {
+        (**self).value(value);
+    }
+
+    fn end_array(&mut self) {
+        (**self).end_array();
+    }
+
+    fn end_object(&mut self) {
+        (**self).end_object();
+    }
+}
+
+// tells both visitors, the first one first
+impl<A: JsonVisitor, B: JsonVisitor> JsonVisitor for (A, B) {
+    fn start_object(&mut self) {
+        self.0.start_object();
+        self.1.start_object();
+    }
+
+    fn duplicate_key(&mut self, key: &str, span: Span) {
+        self.0.duplicate_key(key, span);
+        self.1.duplicate_key(key, span);
+    }
+
+    fn key(&mut self, key: String) {
+        self.0.key(key.clone());
+        self.1.key(key);
+    }
+
+    fn start_array(&mut self) {
+        self.0.start_array();
+        self.1.start_array();
+    }
+
+    fn value(&mut self, value: JsonValue) {
+        self.0.value(value.clone());
+        self.1.value(value);
+    }
+
+    fn end_array(&mut self) {
+        self.0.end_array();
+        self.1.end_array();
+    }
+
+    fn end_object(&mut self) {
+        self.0.end_object();
+        self.1.end_object();
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonEvent {
+    StartObject,
+    Key(String),
+    StartArray,
+    // a string, number, boolean or null
+    Value(JsonValue),
+    EndArray,
+    EndObject,
+}
+
+// collects the events, for instance of a `PushParser`
+impl JsonVisitor for VecDeque<JsonEvent> {
+    fn start_object(&mut self) {
+        self.push_back(JsonEvent::StartObject);
+    }
+
+    fn key(&mut self, key: String) {
+        self.push_back(JsonEvent::Key(key));
+    }
+
+    fn start_array(&mut self) {
+        self.push_back(JsonEvent::StartArray);
+    }
+
+    fn value(&mut self, value: JsonValue) {
+        self.push_back(JsonEvent::Value(value));
+    }
+
+    fn end_array(&mut self) {
+        self.push_back(JsonEvent::EndArray);
+    }
+
+    fn end_object(&mut self) {
+        self.push_back(JsonEvent::EndObject);
+    }
+}
+
+pub fn visit<R: BufRead, V: JsonVisitor>(
+    reader: R,
+    options: &ParserOptions,
+    visitor: &mut V,
+) -> Result<(), JSONError> {
+    parse_with(Lexer::with_options(reader, options), options, visitor)
+}
+
+// the events of a document, read as they are pulled
+pub struct Events<R> {
+    tokens: Lexer<R>,
+    state: State,
+    events: VecDeque<JsonEvent>,
+    end: Position,
+    done: bool,
+}
+
+impl<R: BufRead> Iterator for Events<R> {
+    type Item = Result<JsonEvent, JSONError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(event) = self.events.pop_front() {
+                return Some(Ok(event));
+            }
+            if self.done {
+                return None;
+            }
+            let result = match self.tokens.next() {
+                Some(Ok(token)) => {
+                    self.end = token.span.end;
+                    self.state.step(token, &mut self.events)
+                }
+                Some(Err(err)) => Err(err),
+                None => {
+                    self.done = true;
+                    self.state.finish(self.end)
+                }
+            };
+            if let Err(err) = result {
+                self.done = true;
+                return Some(Err(err));
+            }
+        }
+    }
+}
+
+pub fn parse_events<R: BufRead>(reader: R, options: &ParserOptions) -> Events<R> {
+    Events {
+        tokens: Lexer::with_options(reader, options),
+        state: State::new(options),
+        events: VecDeque::new(),
+        end: Position::start(),
+        done: false,
+    }
+}
+
+#[cfg(test)]
+mod events_tests {
+    use crate::parser::{
+        parse_events, visit, DuplicateKeys, JsonEvent, JsonNumber, JsonValue, JsonVisitor,
+        ParserOptions, Span,
+    };
+
+    #[test]
+    fn should_pull_events() {
+        let events: Result<Vec<JsonEvent>, String> = parse_events(
+            "{\"a\": [1, true], \"b\": {}, \"c\": null}".as_bytes(),
+            &ParserOptions::default(),
+        )
+        .map(|event| event.map_err(|err| err.to_string()))
+        .collect();
+        assert_eq!(
+            Ok(vec![
+                JsonEvent::StartObject,
+                JsonEvent::Key("a".to_string()),
+                JsonEvent::StartArray,
+                JsonEvent::Value(JsonValue::Number(JsonNumber::from(1.))),
+                JsonEvent::Value(JsonValue::Bool(true)),
+                JsonEvent::EndArray,
+                JsonEvent::Key("b".to_string()),
+                JsonEvent::StartObject,
+                JsonEvent::EndObject,
+                JsonEvent::Key("c".to_string()),
+                JsonEvent::Value(JsonValue::Null),
+                JsonEvent::EndObject,
+            ]),
+            events
+        );
+    }
+
+    #[test]
+    fn should_pull_events_before_error() {
+        let mut events = parse_events("[\"a\", }".as_bytes(), &ParserOptions::default());
+        assert_eq!(Some(Ok(JsonEvent::StartArray)), events.next());
+        assert_eq!(
+            Some(Ok(JsonEvent::Value(JsonValue::String("a".to_string())))),
+            events.next()
+        );
+        assert_eq!(
+            "Unexpected '}', expected a value after ',': at line 1, column 7",
+            events.next().unwrap().unwrap_err().to_string()
+        );
+        assert_eq!(None, events.next());
+
+        let mut events = parse_events("[".as_bytes(), &ParserOptions::default());
+        assert_eq!(Some(Ok(JsonEvent::StartArray)), events.next());
+        assert_eq!(
+            "Unexpected EOF: at line 1, column 2",
+            events.next().unwrap().unwrap_err().to_string()
+        );
+        assert_eq!(None, events.next());
+    }
+
+    #[test]
+    fn should_visit_only_what_is_needed() {
+        // counts the records of the top-level array and collects their ids
+        #[derive(Default)]
+        struct Ids {
+            depth: usize,
+            records: usize,
+            in_id: bool,
+            ids: Vec<f64>,
+        }
+        impl JsonVisitor for Ids {
+            fn start_object(&mut self) {
+                self.depth += 1;
+                if self.depth == 2 {
+                    self.records += 1;
+                }
+            }
+            fn key(&mut self, key: String) {
+                self.in_id = self.depth == 2 && key == "id";
+            }
+            fn value(&mut self, value: JsonValue) {
+                if self.in_id {
+                    self.ids.extend(value.as_f64());
+                }
+                self.in_id = false;
+            }
+            fn start_array(&mut self) {
+                self.depth += 1;
+                self.in_id = false;
+            }
+            fn end_array(&mut self) {
+                self.depth -= 1;
+            }
+            fn end_object(&mut self) {
+                self.depth -= 1;
+            }
+        }
+
+        let input = "[{\"id\": 1, \"tags\": [{\"id\": 9}]}, {\"name\": \"x\", \"id\": 2}, {}]";
+        let mut ids = Ids::default();
+        visit(input.as_bytes(), &ParserOptions::default(), &mut ids).unwrap();
+        assert_eq!(3, ids.records);
+        assert_eq!(vec![1., 2.], ids.ids);
+    }
+
+    #[test]
+    fn should_tell_visitor_about_duplicate_keys() {
+        #[derive(Default)]
+        struct Duplicates(Vec<(String, usize, usize)>);
+        impl JsonVisitor for Duplicates {
+            fn duplicate_key(&mut self, key: &str, span: Span) {
+                self.0
+                    .push((key.to_string(), span.start.line, span.start.column));
+            }
+        }
+
+        let input = "{\"a\": {\"a\": 1, \"b\": 2},\n \"b\": 3, \"a\": 4, \"a\": 5}";
+        for policy in [
+            DuplicateKeys::Warn,
+            DuplicateKeys::FirstWins,
+            DuplicateKeys::LastWins,
+        ] {
+            let mut duplicates = Duplicates::default();
+            let options = ParserOptions::new().duplicate_keys(policy);
+            visit(input.as_bytes(), &options, &mut duplicates).unwrap();
+            assert_eq!(
+                vec![("a".to_string(), 2, 10), ("a".to_string(), 2, 18)],
+                duplicates.0
+            );
+        }
+        let mut duplicates = Duplicates::default();
+        visit(input.as_bytes(), &ParserOptions::default(), &mut duplicates).unwrap();
+        assert!(duplicates.0.is_empty());
+    }
+}
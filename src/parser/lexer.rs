@@ -1,6 +1,16 @@
-use std::io::BufRead;
-
-use super::{error::JSONError, token::Token};
+use std::{
+    collections::VecDeque,
+    io::{self, BufRead},
+    mem, str,
+};
+
+use super::{
+    encoding::Encoding,
+    error::{JSONError, JSONErrorKind},
+    number::JsonNumber,
+    options::{AllowedEncodings, Dialect, InvalidUtf8, ParserOptions},
+    token::{Position, Span, SpannedToken, Token},
+};
 
 #[derive(PartialEq, Clone, Copy)]
 enum NumberState {
@@ -13,15 +23,21 @@ enum NumberState {
     Integer,
     Decimal,
     ExpInteger,
+
+    // JSON5 hexadecimal numbers, after "0x" and after at least one digit
+    HexPrefix,
+    HexInteger,
 }
 
 impl NumberState {
+    #[allow(clippy::match_like_matches_macro)]
     pub fn is_final(self) -> bool {
         match self {
             Self::LeadingZero
             | Self::Integer
             | Self::Decimal
-            | Self::ExpInteger => true,
+            | Self::ExpInteger
+            | Self::HexInteger => true,
             _ => false,
         }
     }
@@ -35,7 +51,7 @@ impl NumberState {
     }
 }
 
-#[derive(PartialEq, Clone)]
+#[derive(PartialEq, Clone, Copy)]
 enum State {
     Normal,
 
@@ -47,253 +63,1045 @@ enum State {
 
     ValueStringLiteral,
     Escaping,
-    Hex(u16),
+    // number of hex digits read so far and the code unit they make up
+    Hex(u8, u16),
+
+    // JSON5 only
+    Identifier,
+    // after a backslash and a carriage return, which a line feed can follow
+    EscapedCarriageReturn,
+    // after "\0", which a digit cannot follow
+    EscapedZero,
+    CommentStart,
+    LineComment,
+    BlockComment,
+    BlockCommentStar,
 }
 
-fn parse_string_number_to_float(number_string: String) -> Result<f64, JSONError> {
-    number_string
-        .parse()
-        .map_err(|e| JSONError::new(format!("Unexpected error: {}", e), 1))
+fn unescape(c: char) -> Option<char> {
+    match c {
+        '"' | '\\' | '/' => Some(c),
+        'b' => Some('\u{08}'),
+        'f' => Some('\u{0C}'),
+        'n' => Some('\n'),
+        'r' => Some('\r'),
+        't' => Some('\t'),
+        _ => None,
+    }
 }
 
-pub fn lex<R: BufRead>(mut reader: R) -> Result<Vec<Token>, JSONError> {
-    let mut tokens = Vec::new();
-
-    loop {
-        let mut buf = Vec::<u8>::new();
-        match reader.read_until(b'\n', &mut buf) {
-            Ok(0) => {
-                return Ok(tokens);
-            }
-            Ok(_) => {
-                let s = String::from_utf8(buf).expect("from_utf8 failed");
-                let mut curr_string_literal = String::new();
-                let mut curr_number_string = String::new();
-                let mut state = State::Normal;
-                for c in s.chars() {
-                    state = match (c, &state) {
-                        ('\\', State::ValueStringLiteral) => State::Escaping,
-                        ('\t', State::ValueStringLiteral) => {
-                            return Err(JSONError::new("Unexpected <tab>".to_string(), 1))
-                        }
-                        ('"', State::ValueStringLiteral) => {
-                            tokens.push(Token::StringLiteral(curr_string_literal.clone()));
-                            curr_string_literal.clear();
-                            State::Normal
-                        }
-                        (_, State::ValueStringLiteral) => {
-                            curr_string_literal.push(c);
-                            State::ValueStringLiteral
-                        }
-                        ('"' | '\\', State::Escaping) => {
-                            curr_string_literal.push(c);
-                            State::ValueStringLiteral
-                        }
-                        ('b' | 'f' | 'n' | 'r' | 't' | '/', State::Escaping) => {
-                            curr_string_literal.push('\\');
-                            curr_string_literal.push(c);
-                            State::ValueStringLiteral
-                        }
-                        ('u', State::Escaping) => {
-                            curr_string_literal.push('\\');
-                            curr_string_literal.push('u');
-                            State::Hex(0)
-                        }
-                        ('0'..='9' | 'A'..='F' | 'a'..='f', State::Hex(hex_idx))
-                            if *hex_idx < 4 =>
-                        {
-                            curr_string_literal.push(c);
-                            if *hex_idx == 3 {
-                                State::ValueStringLiteral
-                            } else {
-                                State::Hex(hex_idx + 1)
-                            }
-                        }
+// the escapes JSON5 adds to the JSON ones, any other character stands for itself
+fn unescape_json5(c: char) -> Option<char> {
+    match c {
+        'v' => Some('\u{0B}'),
+        '0'..='9' | 'x' | 'u' => None,
+        _ => Some(c),
+    }
+}
 
-                        ('"', State::Normal) => State::ValueStringLiteral,
+fn is_identifier_start(c: char) -> bool {
+    c.is_alphabetic() || c == '$' || c == '_'
+}
 
-                        ('{', State::Normal) => {
-                            tokens.push(Token::OpenBrace);
-                            State::Normal
-                        }
+fn is_identifier_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '$' || c == '_'
+}
 
-                        ('}', State::Normal) => {
-                            tokens.push(Token::ClosedBrace);
-                            State::Normal
-                        }
-                        ('}', State::ValueNumber(n)) if n.is_final() => {
-                            tokens.push(Token::Number(parse_string_number_to_float(
-                                curr_number_string.clone(),
-                            )?));
-                            curr_number_string.clear();
-                            tokens.push(Token::ClosedBrace);
-                            State::Normal
-                        }
+fn word_token(word: &str, span: Span) -> Result<SpannedToken, JSONError> {
+    let token = match word {
+        "true" => Token::BoolTrue,
+        "false" => Token::BoolFalse,
+        "null" => Token::Null,
+        "Infinity" | "+Infinity" => Token::Number(f64::INFINITY.into()),
+        "-Infinity" => Token::Number(f64::NEG_INFINITY.into()),
+        "NaN" | "+NaN" | "-NaN" => Token::Number(f64::NAN.into()),
+        _ if word.starts_with(['+', '-']) => {
+            return Err(JSONError::new(JSONErrorKind::InvalidNumber, span))
+        }
+        _ => Token::Identifier(word.to_string()),
+    };
+    Ok(SpannedToken::new(token, span))
+}
 
-                        ('[', State::Normal) => {
-                            tokens.push(Token::OpenBracket);
-                            state
-                        }
-                        (']', State::Normal) => {
-                            tokens.push(Token::ClosedBracket);
-                            State::Normal
-                        }
-                        (']', State::ValueNumber(n)) if n.is_final() => {
-                            tokens.push(Token::Number(parse_string_number_to_float(
-                                curr_number_string.clone(),
-                            )?));
-                            curr_number_string.clear();
-
-                            tokens.push(Token::ClosedBracket);
-                            State::Normal
-                        }
+fn combine_surrogates(high: u16, low: u16) -> char {
+    let code = 0x10000 + ((u32::from(high) - 0xD800) << 10) + (u32::from(low) - 0xDC00);
+    char::from_u32(code).expect("a surrogate pair always encodes a valid char")
+}
 
-                        ('\n', State::Normal) => {
-                            tokens.push(Token::NewLine);
-                            State::Normal
-                        }
-                        ('\n', State::ValueNumber(n)) if n.is_final() => {
-                            tokens.push(Token::Number(parse_string_number_to_float(
-                                curr_number_string.clone(),
-                            )?));
-                            curr_number_string.clear();
-                            tokens.push(Token::NewLine);
-                            State::Normal
-                        }
+fn unpaired_surrogate((code, span): (u16, Span)) -> JSONError {
+    JSONError::new(JSONErrorKind::UnpairedSurrogate(code), span)
+}
 
-                        (':', State::Normal) => {
-                            tokens.push(Token::Column);
-                            State::Normal
-                        }
+fn is_number_char(c: char) -> bool {
+    matches!(c, '0'..='9' | '.' | 'e' | 'E' | '+' | '-')
+}
 
-                        (',', State::Normal) => {
-                            tokens.push(Token::Comma);
-                            State::Normal
-                        }
-                        (',', State::ValueNumber(n)) if n.is_final() => {
-                            tokens.push(Token::Number(parse_string_number_to_float(
-                                curr_number_string.clone(),
-                            )?));
-                            curr_number_string.clear();
-                            tokens.push(Token::Comma);
-                            State::Normal
-                        }
+// how much input is lexed at once, so that only a bounded number of tokens is
+// pending even when the whole input is already in memory
+pub(crate) const CHUNK_SIZE: usize = 8 * 1024;
 
-                        (' ', State::Normal) => State::Normal,
-                        (' ', State::ValueNumber(n)) if n.is_final() => {
-                            tokens.push(Token::Number(parse_string_number_to_float(
-                                curr_number_string.clone(),
-                            )?));
-                            curr_number_string.clear();
-                            State::Normal
-                        }
+fn number_token(number_string: &mut String, span: Span) -> SpannedToken {
+    let number = JsonNumber::from_lexeme(number_string);
+    number_string.clear();
+    SpannedToken::new(Token::Number(number), span)
+}
 
-                        ('-' | '+', State::Normal) => {
-                            if c == '-' {
-                                curr_number_string.push(c);
-                            }
-                            State::ValueNumber(NumberState::Sign)
-                        }
-                        ('-' | '+', State::ValueNumber(NumberState::Exp)) => {
-                            if c == '-' {
-                                curr_number_string.push(c);
-                            }
-                            State::ValueNumber(NumberState::ExpSign)
-                        }
-                        ('e' | 'E', State::ValueNumber(n)) if n.is_final() && !n.is_exp() => {
-                            curr_number_string.push('e');
-                            State::ValueNumber(NumberState::Exp)
-                        }
-                        ('0', State::Normal | State::ValueNumber(NumberState::Sign)) => {
-                            curr_number_string.push('0');
-                            State::ValueNumber(NumberState::LeadingZero)
-                        }
-                        (
-                            '0'..='9',
-                            State::ValueNumber(NumberState::Exp | NumberState::ExpSign),
-                        ) => {
-                            curr_number_string.push(c);
-                            State::ValueNumber(NumberState::ExpInteger)
-                        }
-                        ('1'..='9', State::Normal | State::ValueNumber(NumberState::Sign)) => {
-                            curr_number_string.push(c);
-                            State::ValueNumber(NumberState::Integer)
-                        }
-                        ('0'..='9', State::ValueNumber(NumberState::Point)) => {
-                            curr_number_string.push(c);
-                            State::ValueNumber(NumberState::Decimal)
-                        }
-                        ('0'..='9', State::ValueNumber(n_type)) if !n_type.is_leading_zero() => {
-                            curr_number_string.push(c);
-                            State::ValueNumber(*n_type)
-                        }
-                        (
-                            '.',
-                            State::ValueNumber(NumberState::Integer | NumberState::LeadingZero),
-                        ) => {
-                            curr_number_string.push('.');
-                            State::ValueNumber(NumberState::Point)
-                        }
+// the lexer state machine, fed with the input a buffer at a time
+pub(crate) struct Scanner {
+    cursor: Position,
+    pending: VecDeque<SpannedToken>,
+    options: ParserOptions,
+    record_separators: bool,
+    // the token being read, which can go on in the next buffer
+    state: State,
+    token_start: Position,
+    curr_string_literal: String,
+    curr_number_string: String,
+    curr_word: String,
+    quote: char,
+    escape_start: Position,
+    high_surrogate: Option<(u16, Span)>,
+    // detected from the first bytes of the input
+    encoding: Option<Encoding>,
+    // the start of a character cut at the end of a buffer
+    bytes: Vec<u8>,
+}
 
-                        ('t', State::Normal) => State::ValueTrue('t'),
-                        ('r', State::ValueTrue('t')) => State::ValueTrue('r'),
-                        ('u', State::ValueTrue('r')) => State::ValueTrue('u'),
-                        ('e', State::ValueTrue('u')) => {
-                            tokens.push(Token::BoolTrue);
-                            State::Normal
-                        }
+impl Scanner {
+    pub fn new(options: &ParserOptions) -> Scanner {
+        Scanner {
+            cursor: Position::start(),
+            pending: VecDeque::new(),
+            options: options.clone(),
+            record_separators: false,
+            state: State::Normal,
+            token_start: Position::start(),
+            curr_string_literal: String::new(),
+            curr_number_string: String::new(),
+            curr_word: String::new(),
+            quote: '"',
+            escape_start: Position::start(),
+            high_surrogate: None,
+            encoding: None,
+            bytes: Vec::new(),
+        }
+    }
 
-                        ('f', State::Normal) => State::ValueFalse('f'),
-                        ('a', State::ValueFalse('f')) => State::ValueFalse('a'),
-                        ('l', State::ValueFalse('a')) => State::ValueFalse('l'),
-                        ('s', State::ValueFalse('l')) => State::ValueFalse('s'),
-                        ('e', State::ValueFalse('s')) => {
-                            tokens.push(Token::BoolFalse);
-                            State::Normal
-                        }
+    pub fn pop(&mut self) -> Option<SpannedToken> {
+        self.pending.pop_front()
+    }
 
-                        ('n', State::Normal) => State::ValueNull('n'),
-                        ('u', State::ValueNull('n')) => State::ValueNull('u'),
-                        ('l', State::ValueNull('u')) => State::ValueNull('l'),
-                        ('l', State::ValueNull('l')) => {
-                            tokens.push(Token::Null);
-                            State::Normal
-                        }
+    fn is_extra_whitespace(&self, c: char) -> bool {
+        match self.options.dialect {
+            Dialect::Classic => false,
+            Dialect::Strict => matches!(c, '\t' | '\r'),
+            Dialect::Json5 => c.is_whitespace() || c == '\u{FEFF}',
+        }
+    }
 
-                        (_, _) => return Err(JSONError::new(format!("Unexpected '{}'", c), 1)),
+    // lexes `input` into `pending`, on error the tokens before it are left there
+    pub fn push(&mut self, mut input: &[u8]) -> Result<(), JSONError> {
+        // starts with the bytes of a character cut at the end of the previous
+        // input, which four more bytes are enough to complete
+        if !self.bytes.is_empty() {
+            let mut bytes = mem::take(&mut self.bytes);
+            let (cut, taken) = (bytes.len(), input.len().min(4));
+            bytes.extend_from_slice(&input[..taken]);
+            let read = self.decode(&bytes, false)?;
+            if taken == input.len() {
+                bytes.drain(..read);
+                self.bytes = bytes;
+                return Ok(());
+            }
+            input = &input[read - cut..];
+        }
+        let read = self.decode(input, false)?;
+        self.bytes.extend_from_slice(&input[read..]);
+        Ok(())
+    }
+
+    // lexes the characters `bytes` start with and returns how many bytes they
+    // took, at the end of the input the bytes left over are invalid
+    fn decode(&mut self, bytes: &[u8], at_end: bool) -> Result<usize, JSONError> {
+        let encoding = match self.encoding {
+            Some(encoding) => encoding,
+            // the first four bytes tell the encoding
+            None if bytes.len() < 4 && !at_end => return Ok(0),
+            None => {
+                let (encoding, bom) = Encoding::detect(bytes);
+                if encoding != Encoding::Utf8 && self.options.encodings() == AllowedEncodings::Utf8
+                {
+                    return Err(JSONError::new(
+                        JSONErrorKind::UnsupportedEncoding(encoding),
+                        Span::at(self.cursor),
+                    ));
+                }
+                self.encoding = Some(encoding);
+                // the byte order mark is not part of the document
+                self.cursor.offset += bom;
+                return Ok(bom + self.decode(&bytes[bom..], at_end)?);
+            }
+        };
+        let read = match encoding {
+            Encoding::Utf8 => self.decode_utf8(bytes)?,
+            Encoding::Utf16Le => self.decode_utf16(bytes, u16::from_le_bytes)?,
+            Encoding::Utf16Be => self.decode_utf16(bytes, u16::from_be_bytes)?,
+            Encoding::Utf32Le => self.decode_utf32(bytes, u32::from_le_bytes)?,
+            Encoding::Utf32Be => self.decode_utf32(bytes, u32::from_be_bytes)?,
+        };
+        if at_end && read < bytes.len() {
+            self.invalid_input(bytes.len() - read)?;
+            return Ok(bytes.len());
+        }
+        Ok(read)
+    }
+
+    fn decode_utf8(&mut self, bytes: &[u8]) -> Result<usize, JSONError> {
+        let mut read = 0;
+        loop {
+            let rest = &bytes[read..];
+            let (valid_up_to, error_len) = match str::from_utf8(rest) {
+                Ok(_) => (rest.len(), None),
+                Err(err) => (err.valid_up_to(), err.error_len()),
+            };
+            for c in str::from_utf8(&rest[..valid_up_to])
+                .expect("checked to be valid")
+                .chars()
+            {
+                self.lex_char(c, c.len_utf8())?;
+            }
+            read += valid_up_to;
+            match error_len {
+                // without an error length the character goes on in the next input
+                None => return Ok(read),
+                Some(len) => {
+                    self.invalid_input(len)?;
+                    read += len;
+                }
+            }
+        }
+    }
+
+    fn decode_utf16(&mut self, bytes: &[u8], unit: fn([u8; 2]) -> u16) -> Result<usize, JSONError> {
+        let unit_at = |i: usize| bytes.get(i..i + 2).map(|b| unit([b[0], b[1]]));
+        let mut read = 0;
+        while let Some(first) = unit_at(read) {
+            let (c, len) = match (first, unit_at(read + 2)) {
+                (0xD800..=0xDBFF, Some(second @ 0xDC00..=0xDFFF)) => {
+                    (Some(combine_surrogates(first, second)), 4)
+                }
+                // the low surrogate is in the next input
+                (0xD800..=0xDBFF, None) => break,
+                (0xD800..=0xDFFF, _) => (None, 2),
+                _ => (char::from_u32(first.into()), 2),
+            };
+            match c {
+                Some(c) => self.lex_char(c, len)?,
+                None => self.invalid_input(len)?,
+            }
+            read += len;
+        }
+        Ok(read)
+    }
+
+    fn decode_utf32(&mut self, bytes: &[u8], unit: fn([u8; 4]) -> u32) -> Result<usize, JSONError> {
+        let mut read = 0;
+        while let Some(b) = bytes.get(read..read + 4) {
+            match char::from_u32(unit([b[0], b[1], b[2], b[3]])) {
+                Some(c) => self.lex_char(c, 4)?,
+                None => self.invalid_input(4)?,
+            }
+            read += 4;
+        }
+        Ok(read)
+    }
+
+    // `len` bytes that do not decode are either an error or read as U+FFFD
+    fn invalid_input(&mut self, len: usize) -> Result<(), JSONError> {
+        let kind = match (self.options.invalid_utf8, self.encoding) {
+            (InvalidUtf8::Replace, _) => {
+                return self.lex_char(char::REPLACEMENT_CHARACTER, len);
+            }
+            (InvalidUtf8::Error, Some(Encoding::Utf8) | None) => JSONErrorKind::InvalidUtf8,
+            (InvalidUtf8::Error, Some(encoding)) => JSONErrorKind::InvalidEncoding(encoding),
+        };
+        Err(JSONError::new(kind, Span::at(self.cursor)))
+    }
+
+    // `len` is the number of bytes `c` takes in the input
+    fn lex_char(&mut self, c: char, len: usize) -> Result<(), JSONError> {
+        let strict = self.options.dialect == Dialect::Strict;
+        let json5 = self.options.dialect == Dialect::Json5;
+        let separators = self.record_separators;
+        let mut state = self.state;
+        let mut end = self.cursor.advance(c);
+        end.offset = self.cursor.offset + len;
+        let here = Span::new(self.cursor, end);
+        // identifiers have no closing character, they end before the first
+        // one that cannot be part of them
+        if state == State::Identifier && !is_identifier_char(c) {
+            let word_span = Span::new(self.token_start, self.cursor);
+            self.pending.push_back(word_token(&self.curr_word, word_span)?);
+            self.curr_word.clear();
+            state = State::Normal;
+        }
+        // a backslash followed by "\r\n" is a single line continuation
+        if state == State::EscapedCarriageReturn {
+            state = State::ValueStringLiteral;
+            if c == '\n' {
+                self.check_limits(here)?;
+                self.state = state;
+                self.cursor = here.end;
+                return Ok(());
+            }
+        }
+        if state == State::EscapedZero {
+            if c.is_ascii_digit() {
+                return Err(JSONError::new(JSONErrorKind::InvalidEscape(c), here));
+            }
+            state = State::ValueStringLiteral;
+        }
+        if state == State::Normal {
+            self.token_start = self.cursor;
+        }
+        let number_span = Span::new(self.token_start, self.cursor);
+        state = match (c, &state) {
+            // a high surrogate escape must be followed right away by a low one
+            (_, State::ValueStringLiteral) if c != '\\' && self.high_surrogate.is_some() => {
+                return Err(unpaired_surrogate(self.high_surrogate.unwrap()))
+            }
+            (_, State::Escaping) if c != 'u' && self.high_surrogate.is_some() => {
+                return Err(unpaired_surrogate(self.high_surrogate.unwrap()))
+            }
+
+            ('\\', State::ValueStringLiteral) => {
+                self.escape_start = self.cursor;
+                State::Escaping
+            }
+            ('\t', State::ValueStringLiteral) if !json5 => {
+                return Err(JSONError::new(JSONErrorKind::ControlCharacter(c), here))
+            }
+            ('\u{00}'..='\u{1F}', State::ValueStringLiteral) if strict => {
+                return Err(JSONError::new(JSONErrorKind::ControlCharacter(c), here))
+            }
+            ('\n', State::ValueStringLiteral) => {
+                return Err(JSONError::new(JSONErrorKind::ControlCharacter(c), here))
+            }
+            (_, State::ValueStringLiteral) if c == self.quote => {
+                self.pending.push_back(SpannedToken::new(
+                    Token::StringLiteral(self.curr_string_literal.clone()),
+                    Span::new(self.token_start, here.end),
+                ));
+                self.curr_string_literal.clear();
+                State::Normal
+            }
+            (_, State::ValueStringLiteral) => {
+                self.curr_string_literal.push(c);
+                State::ValueStringLiteral
+            }
+            ('u', State::Escaping) => State::Hex(0, 0),
+            // "\xHH" is read like "\u00HH"
+            ('x', State::Escaping) if json5 => State::Hex(2, 0),
+            // a JSON5 line continuation
+            ('\n' | '\u{2028}' | '\u{2029}', State::Escaping) if json5 => State::ValueStringLiteral,
+            ('\r', State::Escaping) if json5 => State::EscapedCarriageReturn,
+            ('0', State::Escaping) if json5 => {
+                self.curr_string_literal.push('\0');
+                State::EscapedZero
+            }
+            (_, State::Escaping) => {
+                match unescape(c).or_else(|| unescape_json5(c).filter(|_| json5)) {
+                    Some(unescaped) => self.curr_string_literal.push(unescaped),
+                    None => return Err(JSONError::new(JSONErrorKind::InvalidEscape(c), here)),
+                }
+                State::ValueStringLiteral
+            }
+            (_, State::Hex(digits, code)) if c.is_ascii_hexdigit() => {
+                let code = (code << 4) | c.to_digit(16).unwrap() as u16;
+                if *digits < 3 {
+                    State::Hex(digits + 1, code)
+                } else {
+                    let escape = Span::new(self.escape_start, here.end);
+                    match (self.high_surrogate.take(), code) {
+                        (None, 0xD800..=0xDBFF) => self.high_surrogate = Some((code, escape)),
+                        (Some((high, _)), 0xDC00..=0xDFFF) => {
+                            self.curr_string_literal.push(combine_surrogates(high, code))
+                        }
+                        (Some(high), _) => return Err(unpaired_surrogate(high)),
+                        (None, 0xDC00..=0xDFFF) => {
+                            return Err(unpaired_surrogate((code, escape)))
+                        }
+                        (None, _) => self.curr_string_literal
+                            .push(char::from_u32(code.into()).expect("not a surrogate")),
                     }
+                    State::ValueStringLiteral
                 }
-                if state != State::Normal {
-                    return Err(JSONError::new(format!("Unexpected EOF"), 1));
+            }
+
+            ('"', State::Normal) => {
+                self.quote = c;
+                State::ValueStringLiteral
+            }
+            ('\'', State::Normal) if json5 => {
+                self.quote = c;
+                State::ValueStringLiteral
+            }
+
+            (_, State::Normal) if json5 && is_identifier_start(c) => {
+                self.curr_word.push(c);
+                State::Identifier
+            }
+            ('I' | 'N', State::ValueNumber(NumberState::Sign)) if json5 => {
+                // '+' is the only sign not kept in the number string
+                self.curr_word.push(if self.curr_number_string.is_empty() { '+' } else { '-' });
+                self.curr_word.push(c);
+                self.curr_number_string.clear();
+                State::Identifier
+            }
+            (_, State::Identifier) => {
+                self.curr_word.push(c);
+                State::Identifier
+            }
+
+            ('/', State::Normal) if json5 => State::CommentStart,
+            ('/', State::ValueNumber(n)) if json5 && n.is_final() => {
+                self.pending.push_back(number_token(&mut self.curr_number_string, number_span));
+                State::CommentStart
+            }
+            ('/', State::CommentStart) => State::LineComment,
+            ('*', State::CommentStart) => State::BlockComment,
+            ('\n', State::LineComment) => {
+                self.pending.push_back(SpannedToken::new(Token::NewLine, here));
+                State::Normal
+            }
+            // like anywhere else, only "\n" is a new line token
+            ('\r' | '\u{2028}' | '\u{2029}', State::LineComment) => State::Normal,
+            (_, State::LineComment) => State::LineComment,
+            ('*', State::BlockComment | State::BlockCommentStar) => State::BlockCommentStar,
+            ('/', State::BlockCommentStar) => State::Normal,
+            (_, State::BlockComment | State::BlockCommentStar) => State::BlockComment,
+
+            ('{', State::Normal) => {
+                self.pending.push_back(SpannedToken::new(Token::OpenBrace, here));
+                State::Normal
+            }
+
+            ('}', State::Normal) => {
+                self.pending.push_back(SpannedToken::new(Token::ClosedBrace, here));
+                State::Normal
+            }
+            ('}', State::ValueNumber(n)) if n.is_final() => {
+                self.pending.push_back(number_token(&mut self.curr_number_string, number_span));
+                self.pending.push_back(SpannedToken::new(Token::ClosedBrace, here));
+                State::Normal
+            }
+
+            ('[', State::Normal) => {
+                self.pending.push_back(SpannedToken::new(Token::OpenBracket, here));
+                state
+            }
+            (']', State::Normal) => {
+                self.pending.push_back(SpannedToken::new(Token::ClosedBracket, here));
+                State::Normal
+            }
+            (']', State::ValueNumber(n)) if n.is_final() => {
+                self.pending.push_back(number_token(&mut self.curr_number_string, number_span));
+                self.pending.push_back(SpannedToken::new(Token::ClosedBracket, here));
+                State::Normal
+            }
+
+            ('\n', State::Normal) => {
+                self.pending.push_back(SpannedToken::new(Token::NewLine, here));
+                State::Normal
+            }
+            ('\n', State::ValueNumber(n)) if n.is_final() => {
+                self.pending.push_back(number_token(&mut self.curr_number_string, number_span));
+                self.pending.push_back(SpannedToken::new(Token::NewLine, here));
+                State::Normal
+            }
+
+            (':', State::Normal) => {
+                self.pending.push_back(SpannedToken::new(Token::Column, here));
+                State::Normal
+            }
+
+            (',', State::Normal) => {
+                self.pending.push_back(SpannedToken::new(Token::Comma, here));
+                State::Normal
+            }
+            (',', State::ValueNumber(n)) if n.is_final() => {
+                self.pending.push_back(number_token(&mut self.curr_number_string, number_span));
+                self.pending.push_back(SpannedToken::new(Token::Comma, here));
+                State::Normal
+            }
+
+            ('\u{1E}', State::Normal) if separators => {
+                self.pending.push_back(SpannedToken::new(Token::RecordSeparator, here));
+                State::Normal
+            }
+            ('\u{1E}', State::ValueNumber(n)) if separators && n.is_final() => {
+                self.pending.push_back(number_token(&mut self.curr_number_string, number_span));
+                self.pending.push_back(SpannedToken::new(Token::RecordSeparator, here));
+                State::Normal
+            }
+
+            (' ', State::Normal) => State::Normal,
+            (_, State::Normal) if self.is_extra_whitespace(c) => State::Normal,
+            (' ', State::ValueNumber(n)) if n.is_final() => {
+                self.pending.push_back(number_token(&mut self.curr_number_string, number_span));
+                State::Normal
+            }
+            (_, State::ValueNumber(n)) if self.is_extra_whitespace(c) && n.is_final() => {
+                self.pending.push_back(number_token(&mut self.curr_number_string, number_span));
+                State::Normal
+            }
+
+            ('-', State::Normal) => {
+                self.curr_number_string.push(c);
+                State::ValueNumber(NumberState::Sign)
+            }
+            ('+', State::Normal) if !strict => State::ValueNumber(NumberState::Sign),
+            ('-' | '+', State::ValueNumber(NumberState::Exp)) => {
+                self.curr_number_string.push(c);
+                State::ValueNumber(NumberState::ExpSign)
+            }
+            ('x' | 'X', State::ValueNumber(NumberState::LeadingZero)) if json5 => {
+                self.curr_number_string.push(c);
+                State::ValueNumber(NumberState::HexPrefix)
+            }
+            (
+                _,
+                State::ValueNumber(NumberState::HexPrefix | NumberState::HexInteger),
+            ) if c.is_ascii_hexdigit() => {
+                self.curr_number_string.push(c);
+                State::ValueNumber(NumberState::HexInteger)
+            }
+            ('.', State::Normal | State::ValueNumber(NumberState::Sign)) if json5 => {
+                self.curr_number_string.push('.');
+                State::ValueNumber(NumberState::Point)
+            }
+            ('e' | 'E', State::ValueNumber(n)) if n.is_final() && !n.is_exp() => {
+                self.curr_number_string.push(c);
+                State::ValueNumber(NumberState::Exp)
+            }
+            ('0', State::Normal | State::ValueNumber(NumberState::Sign)) => {
+                self.curr_number_string.push('0');
+                State::ValueNumber(NumberState::LeadingZero)
+            }
+            (
+                '0'..='9',
+                State::ValueNumber(NumberState::Exp | NumberState::ExpSign),
+            ) => {
+                self.curr_number_string.push(c);
+                State::ValueNumber(NumberState::ExpInteger)
+            }
+            ('1'..='9', State::Normal | State::ValueNumber(NumberState::Sign)) => {
+                self.curr_number_string.push(c);
+                State::ValueNumber(NumberState::Integer)
+            }
+            ('0'..='9', State::ValueNumber(NumberState::Point)) => {
+                self.curr_number_string.push(c);
+                State::ValueNumber(NumberState::Decimal)
+            }
+            ('0'..='9', State::ValueNumber(n_type)) if !n_type.is_leading_zero() => {
+                self.curr_number_string.push(c);
+                State::ValueNumber(*n_type)
+            }
+            (
+                '.',
+                State::ValueNumber(NumberState::Integer | NumberState::LeadingZero),
+            ) => {
+                self.curr_number_string.push('.');
+                // JSON5 numbers can end with the decimal point
+                if json5 {
+                    State::ValueNumber(NumberState::Decimal)
+                } else {
+                    State::ValueNumber(NumberState::Point)
                 }
+            }
+
+            ('t', State::Normal) => State::ValueTrue('t'),
+            ('r', State::ValueTrue('t')) => State::ValueTrue('r'),
+            ('u', State::ValueTrue('r')) => State::ValueTrue('u'),
+            ('e', State::ValueTrue('u')) => {
+                self.pending.push_back(SpannedToken::new(
+                    Token::BoolTrue,
+                    Span::new(self.token_start, here.end),
+                ));
+                State::Normal
+            }
+
+            ('f', State::Normal) => State::ValueFalse('f'),
+            ('a', State::ValueFalse('f')) => State::ValueFalse('a'),
+            ('l', State::ValueFalse('a')) => State::ValueFalse('l'),
+            ('s', State::ValueFalse('l')) => State::ValueFalse('s'),
+            ('e', State::ValueFalse('s')) => {
+                self.pending.push_back(SpannedToken::new(
+                    Token::BoolFalse,
+                    Span::new(self.token_start, here.end),
+                ));
+                State::Normal
+            }
+
+            ('n', State::Normal) => State::ValueNull('n'),
+            ('u', State::ValueNull('n')) => State::ValueNull('u'),
+            ('l', State::ValueNull('u')) => State::ValueNull('l'),
+            ('l', State::ValueNull('l')) => {
+                self.pending.push_back(SpannedToken::new(
+                    Token::Null,
+                    Span::new(self.token_start, here.end),
+                ));
+                State::Normal
+            }
 
-                buf = s.into_bytes();
-                buf.clear();
+            (_, State::Hex(..)) => {
+                return Err(JSONError::new(JSONErrorKind::InvalidEscape(c), here))
+            }
+            (_, State::ValueNumber(n)) if !n.is_final() || is_number_char(c) => {
+                return Err(JSONError::new(JSONErrorKind::InvalidNumber, here))
+            }
+            (_, _) => {
+                return Err(JSONError::new(JSONErrorKind::UnexpectedChar(c), here))
             }
+        };
+        self.check_limits(here)?;
+        self.state = state;
+        self.cursor = here.end;
+        Ok(())
+    }
+
+    // checked after every character, so that nothing the input is made of can
+    // grow past its limit before being rejected
+    fn check_limits(&self, here: Span) -> Result<(), JSONError> {
+        let options = &self.options;
+        let token = Span::new(self.token_start, here.end);
+        let exceeded = |limit: Option<usize>, length: usize| limit.filter(|limit| length > *limit);
+        if let Some(limit) = exceeded(options.max_size, here.end.offset) {
+            return Err(JSONError::new(JSONErrorKind::SizeLimitExceeded(limit), here));
+        }
+        // unquoted JSON5 keys are strings too
+        let string_length = self.curr_string_literal.len().max(self.curr_word.len());
+        if let Some(limit) = exceeded(options.max_string_length, string_length) {
+            return Err(JSONError::new(JSONErrorKind::StringLengthLimitExceeded(limit), token));
+        }
+        if let Some(limit) = exceeded(options.max_number_length, self.curr_number_string.len()) {
+            return Err(JSONError::new(JSONErrorKind::NumberLengthLimitExceeded(limit), token));
+        }
+        Ok(())
+    }
+
+    // the input can only end between tokens, right after a number or an
+    // identifier, or in a line comment
+    pub fn finish(&mut self) -> Result<(), JSONError> {
+        let bytes = mem::take(&mut self.bytes);
+        self.decode(&bytes, true)?;
+        let end_span = Span::new(self.token_start, self.cursor);
+        match self.state {
+            State::ValueNumber(n) if n.is_final() => {
+                let token = number_token(&mut self.curr_number_string, end_span);
+                self.pending.push_back(token);
+            }
+            State::Identifier => self.pending.push_back(word_token(&self.curr_word, end_span)?),
+            State::Normal | State::LineComment => {}
+            _ => {
+                return Err(JSONError::new(
+                    JSONErrorKind::UnexpectedEof,
+                    Span::at(self.cursor),
+                ))
+            }
+        }
+        self.state = State::Normal;
+        Ok(())
+    }
+}
+
+// yields tokens as they are read, so that a document can be processed without
+// holding all of its tokens in memory
+pub struct Lexer<R> {
+    reader: R,
+    scanner: Scanner,
+    error: Option<JSONError>,
+    done: bool,
+}
+
+impl<R: BufRead> Lexer<R> {
+    pub fn new(reader: R) -> Lexer<R> {
+        Lexer::with_options(reader, &ParserOptions::default())
+    }
+
+    pub fn with_options(reader: R, options: &ParserOptions) -> Lexer<R> {
+        Lexer {
+            reader,
+            scanner: Scanner::new(options),
+            error: None,
+            done: false,
+        }
+    }
+
+    // for input that does not start at the beginning of a document
+    pub(crate) fn starting_at(mut self, position: Position) -> Lexer<R> {
+        self.scanner.cursor = position;
+        self
+    }
+
+    // for input in a known encoding, which starts without a byte order mark
+    pub(crate) fn with_encoding(mut self, encoding: Encoding) -> Lexer<R> {
+        self.scanner.encoding = Some(encoding);
+        self
+    }
+
+    // lexes the 0x1E character as a `Token::RecordSeparator`
+    pub(crate) fn with_record_separators(mut self) -> Lexer<R> {
+        self.scanner.record_separators = true;
+        self
+    }
+
+    // lexes the next buffer of input, returns false at the end of the input
+    fn lex_chunk(&mut self) -> Result<bool, JSONError> {
+        let (read, lexed) = match self.reader.fill_buf() {
+            Ok(buf) => {
+                let read = buf.len().min(CHUNK_SIZE);
+                (read, self.scanner.push(&buf[..read]))
+            }
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => return Ok(true),
             Err(err) => {
-                // TODO: implement line count
-                return Err(JSONError::new(err.to_string(), 1));
+                return Err(JSONError::new(
+                    JSONErrorKind::Io(err.into()),
+                    Span::at(self.scanner.cursor),
+                ));
+            }
+        };
+        if read == 0 {
+            self.scanner.finish()?;
+            return Ok(false);
+        }
+        // consumed even on error, so that the reader has seen the input it points at
+        self.reader.consume(read);
+        lexed.map(|()| true)
+    }
+}
+
+impl<R: BufRead> Iterator for Lexer<R> {
+    type Item = Result<SpannedToken, JSONError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            // tokens lexed before an error in the same buffer come first
+            if let Some(token) = self.scanner.pop() {
+                return Some(Ok(token));
+            }
+            if let Some(err) = self.error.take() {
+                return Some(Err(err));
+            }
+            if self.done {
+                return None;
+            }
+            match self.lex_chunk() {
+                Ok(true) => {}
+                Ok(false) => self.done = true,
+                Err(err) => {
+                    self.done = true;
+                    self.error = Some(err);
+                }
             }
         }
     }
 }
 
+pub fn lex<R: BufRead>(reader: R) -> Result<Vec<SpannedToken>, JSONError> {
+    Lexer::new(reader).collect()
+}
+
 #[cfg(test)]
 mod lexer_tests {
     use core::f64;
 
     use super::*;
+    use crate::parser::test_utils::describe;
 
     fn run_test_case_with(input_str: &str, expected_tokens: Vec<Token>) {
         let reader = input_str.as_bytes();
-        let found_tokens = lex(reader).unwrap();
+        let found_tokens: Vec<Token> = lex(reader)
+            .unwrap()
+            .into_iter()
+            .map(|spanned| spanned.token)
+            .collect();
         assert_eq!(found_tokens, expected_tokens);
     }
 
-    fn run_expected_error_test_case_with(input_str: &str, expected_error: JSONError) {
+    fn run_expected_error_test_case_with(
+        input_str: &str,
+        expected_error: (JSONErrorKind, usize, usize),
+    ) {
         let reader = input_str.as_bytes();
-        let found_tokens = lex(reader).unwrap_err();
-        assert_eq!(found_tokens, expected_error);
+        let found_error = lex(reader).unwrap_err();
+        assert_eq!(describe(&found_error), expected_error);
+    }
+
+    fn number(lexeme: &str) -> Token {
+        Token::Number(JsonNumber::from_lexeme(lexeme))
+    }
+
+    fn span(start: (usize, usize, usize), end: (usize, usize, usize)) -> Span {
+        Span::new(
+            Position::new(start.0, start.1, start.2),
+            Position::new(end.0, end.1, end.2),
+        )
+    }
+
+    #[test]
+    fn should_lex_spans_of_single_char_tokens() {
+        let spans: Vec<Span> = lex("{ }".as_bytes())
+            .unwrap()
+            .into_iter()
+            .map(|spanned| spanned.span)
+            .collect();
+        assert_eq!(
+            spans,
+            vec![span((0, 1, 1), (1, 1, 2)), span((2, 1, 3), (3, 1, 4))]
+        );
+    }
+
+    #[test]
+    fn should_lex_spans_of_multi_char_tokens() {
+        let spans: Vec<Span> = lex("[\"ab\", -1.5,\n true]".as_bytes())
+            .unwrap()
+            .into_iter()
+            .map(|spanned| spanned.span)
+            .collect();
+        assert_eq!(
+            spans,
+            vec![
+                span((0, 1, 1), (1, 1, 2)),
+                span((1, 1, 2), (5, 1, 6)),
+                span((5, 1, 6), (6, 1, 7)),
+                span((7, 1, 8), (11, 1, 12)),
+                span((11, 1, 12), (12, 1, 13)),
+                span((12, 1, 13), (13, 2, 1)),
+                span((14, 2, 2), (18, 2, 6)),
+                span((18, 2, 6), (19, 2, 7)),
+            ]
+        );
+    }
+
+    #[test]
+    fn should_count_columns_in_chars_and_offsets_in_bytes() {
+        let tokens = lex("[\"😊\",1]".as_bytes()).unwrap();
+        assert_eq!(tokens[1].span, span((1, 1, 2), (7, 1, 5)));
+        assert_eq!(tokens[3].span, span((8, 1, 6), (9, 1, 7)));
+    }
+
+    #[test]
+    fn should_report_invalid_utf8_instead_of_panicking() {
+        let found_error = lex(&b"[\"ok\",\n \"\xC3\xA9\xFF\"]"[..]).unwrap_err();
+        assert_eq!(describe(&found_error), (JSONErrorKind::InvalidUtf8, 2, 4));
+        assert_eq!(found_error.offset(), 11);
+    }
+
+    fn lex_lossy(input: &[u8], capacity: usize) -> Result<Vec<SpannedToken>, JSONError> {
+        let options = ParserOptions::new().invalid_utf8(InvalidUtf8::Replace);
+        Lexer::with_options(std::io::BufReader::with_capacity(capacity, input), &options).collect()
+    }
+
+    #[test]
+    fn should_replace_invalid_utf8_when_asked() {
+        let input = b"[\"a\xFFb\", \"\xE2\x82\", 1]";
+        for capacity in [1, 2, 3, input.len()] {
+            let tokens = lex_lossy(input, capacity).unwrap();
+            assert_eq!(Token::StringLiteral("a\u{FFFD}b".to_string()), tokens[1].token);
+            assert_eq!(Token::StringLiteral("\u{FFFD}".to_string()), tokens[3].token);
+            // offsets count the bytes of the input, columns the characters read
+            assert_eq!(span((14, 1, 14), (15, 1, 15)), tokens[5].span);
+        }
+        assert_eq!(
+            Err((JSONErrorKind::UnexpectedChar('\u{FFFD}'), 1, 2)),
+            lex_lossy(b"[\xFF]", 1).map_err(|err| describe(&err))
+        );
+        assert_eq!(
+            Err((JSONErrorKind::UnexpectedEof, 1, 4)),
+            lex_lossy(b"[\"\xE2\x82", 1).map_err(|err| describe(&err))
+        );
+    }
+
+    fn lex_in_chunks_of(input: &[u8], capacity: usize) -> Result<Vec<SpannedToken>, JSONError> {
+        lex(std::io::BufReader::with_capacity(capacity, input))
+    }
+
+    #[test]
+    fn should_lex_the_same_whatever_the_chunk_size() {
+        let inputs: [&[u8]; 8] = [
+            b"{\"key\": [1, -2.5e+3, true, false, null]}\n",
+            "[\"é😊\", \"\\ud83d\\ude0a\", 10]".as_bytes(),
+            b"[12345",
+            b"[\"unterminated",
+            b"[tr",
+            b"[\"\xC3\xA9\", \"\xFF\"]",
+            b"[\"\xC3",
+            b"{\"a\": 1.}",
+        ];
+        for input in inputs {
+            for capacity in 1..=4 {
+                assert_eq!(lex(input), lex_in_chunks_of(input, capacity));
+            }
+        }
+    }
+
+    #[test]
+    fn should_lex_single_line_longer_than_a_chunk() {
+        let long_string = "é".repeat(CHUNK_SIZE);
+        let input = format!("[\"{}\", {}]", long_string, "1".repeat(CHUNK_SIZE));
+        let tokens = lex_in_chunks_of(input.as_bytes(), 1000).unwrap();
+        assert_eq!(5, tokens.len());
+        assert_eq!(Token::StringLiteral(long_string), tokens[1].token);
+        assert_eq!(
+            Position::new(input.len(), 1, 2 * CHUNK_SIZE + 7),
+            tokens[4].span.end
+        );
+    }
+
+    fn utf16(input: &str, big_endian: bool) -> Vec<u8> {
+        input
+            .encode_utf16()
+            .flat_map(|unit| {
+                if big_endian {
+                    unit.to_be_bytes()
+                } else {
+                    unit.to_le_bytes()
+                }
+            })
+            .collect()
+    }
+
+    fn utf32(input: &str, big_endian: bool) -> Vec<u8> {
+        input
+            .chars()
+            .map(|c| c as u32)
+            .flat_map(|unit| {
+                if big_endian {
+                    unit.to_be_bytes()
+                } else {
+                    unit.to_le_bytes()
+                }
+            })
+            .collect()
+    }
+
+    fn tokens_of(
+        input: &[u8],
+        capacity: usize,
+    ) -> Result<Vec<Token>, (JSONErrorKind, usize, usize)> {
+        match lex_in_chunks_of(input, capacity) {
+            Ok(tokens) => Ok(tokens.into_iter().map(|spanned| spanned.token).collect()),
+            Err(err) => Err(describe(&err)),
+        }
+    }
+
+    #[test]
+    fn should_skip_utf8_byte_order_mark() {
+        let tokens = lex(&b"\xEF\xBB\xBF[1]"[..]).unwrap();
+        assert_eq!(span((3, 1, 1), (4, 1, 2)), tokens[0].span);
+        assert_eq!(span((4, 1, 2), (5, 1, 3)), tokens[1].span);
+    }
+
+    #[test]
+    fn should_lex_utf16_and_utf32_input() {
+        let text = "{\"é😊\": [1, true]}";
+        let expected = lex(text.as_bytes())
+            .unwrap()
+            .into_iter()
+            .map(|spanned| spanned.token)
+            .collect::<Vec<Token>>();
+        for big_endian in [false, true] {
+            let inputs = [
+                utf16(text, big_endian),
+                utf16(&format!("\u{FEFF}{}", text), big_endian),
+                utf32(text, big_endian),
+                utf32(&format!("\u{FEFF}{}", text), big_endian),
+            ];
+            for input in inputs {
+                for capacity in [1, 2, 3, 5, input.len()] {
+                    assert_eq!(Ok(expected.clone()), tokens_of(&input, capacity));
+                }
+            }
+        }
+        assert_eq!(
+            Ok(vec![Token::Number(JsonNumber::from(7.))]),
+            tokens_of(&utf16("7", false), 1)
+        );
+    }
+
+    #[test]
+    fn should_count_offsets_in_bytes_of_the_input() {
+        let tokens = lex(&utf16("\u{FEFF}[\"😊\"]", true)[..]).unwrap();
+        assert_eq!(span((2, 1, 1), (4, 1, 2)), tokens[0].span);
+        assert_eq!(span((4, 1, 2), (12, 1, 5)), tokens[1].span);
+    }
+
+    #[test]
+    fn should_report_invalid_utf16_and_utf32() {
+        let mut lone_surrogate = utf16("[\"a", false);
+        lone_surrogate.extend_from_slice(&[0x3D, 0xD8, b'"', 0, b']', 0]);
+        assert_eq!(
+            Err((JSONErrorKind::InvalidEncoding(Encoding::Utf16Le), 1, 4)),
+            tokens_of(&lone_surrogate, 3)
+        );
+        let mut truncated = utf16("[\"a\"]", true);
+        truncated.push(0);
+        assert_eq!(
+            Err((JSONErrorKind::InvalidEncoding(Encoding::Utf16Be), 1, 6)),
+            tokens_of(&truncated, 4)
+        );
+        let mut out_of_range = utf32("[", false);
+        out_of_range.extend_from_slice(&0x110000u32.to_le_bytes());
+        assert_eq!(
+            Err((JSONErrorKind::InvalidEncoding(Encoding::Utf32Le), 1, 2)),
+            tokens_of(&out_of_range, 2)
+        );
+
+        let options = ParserOptions::new().invalid_utf8(InvalidUtf8::Replace);
+        let tokens = Lexer::with_options(&lone_surrogate[..], &options)
+            .map(|spanned| spanned.map(|spanned| spanned.token))
+            .collect::<Result<Vec<Token>, JSONError>>();
+        assert_eq!(
+            Ok(vec![
+                Token::OpenBracket,
+                Token::StringLiteral("a\u{FFFD}".to_string()),
+                Token::ClosedBracket
+            ]),
+            tokens
+        );
+    }
+
+    #[test]
+    fn should_accept_only_utf8_in_strict_dialect() {
+        let strict = ParserOptions::new().dialect(Dialect::Strict);
+        let input = utf16("[1]", false);
+        let found_error = Lexer::with_options(&input[..], &strict)
+            .collect::<Result<Vec<SpannedToken>, JSONError>>()
+            .unwrap_err();
+        assert_eq!(
+            (JSONErrorKind::UnsupportedEncoding(Encoding::Utf16Le), 1, 1),
+            describe(&found_error)
+        );
+        assert!(Lexer::with_options(&b"\xEF\xBB\xBF[1]"[..], &strict).all(|token| token.is_ok()));
+
+        let any = strict.allowed_encodings(AllowedEncodings::Any);
+        assert!(Lexer::with_options(&input[..], &any).all(|token| token.is_ok()));
+        let utf8_only = ParserOptions::new().allowed_encodings(AllowedEncodings::Utf8);
+        assert!(Lexer::with_options(&input[..], &utf8_only).any(|token| token.is_err()));
+    }
+
+    #[test]
+    fn should_report_new_line_in_string() {
+        run_expected_error_test_case_with(
+            "[\"two\nlines\"]",
+            (JSONErrorKind::ControlCharacter('\n'), 1, 6),
+        )
+    }
+
+    #[test]
+    fn should_report_error_position_on_later_line() {
+        run_expected_error_test_case_with("{\n\"key\": tru e}", (JSONErrorKind::UnexpectedChar(' '), 2, 11))
     }
 
     #[test]
@@ -322,7 +1130,7 @@ mod lexer_tests {
 
     #[test]
     fn should_report_err_lex_normal_text() {
-        run_expected_error_test_case_with("hello", JSONError::new(format!("Unexpected 'h'"), 1));
+        run_expected_error_test_case_with("hello", (JSONErrorKind::UnexpectedChar('h'), 1, 1));
     }
 
     #[test]
@@ -373,7 +1181,7 @@ mod lexer_tests {
     fn shuold_not_allow_escaping_unallowed_chars() {
         run_expected_error_test_case_with(
             "{\"ab\\c\":\"\"",
-            JSONError::new(format!("Unexpected 'c'"), 1),
+            (JSONErrorKind::InvalidEscape('c'), 1, 6),
         )
     }
 
@@ -441,7 +1249,7 @@ mod lexer_tests {
                 Token::OpenBrace,
                 Token::StringLiteral("key".to_string()),
                 Token::Column,
-                Token::Number(123456789.),
+                Token::Number(JsonNumber::from(123456789.)),
                 Token::ClosedBrace,
             ]),
         )
@@ -455,7 +1263,7 @@ mod lexer_tests {
                 Token::OpenBrace,
                 Token::StringLiteral("key".to_string()),
                 Token::Column,
-                Token::Number(123456789.),
+                Token::Number(JsonNumber::from(123456789.)),
                 Token::NewLine,
                 Token::ClosedBrace,
             ]),
@@ -470,7 +1278,7 @@ mod lexer_tests {
                 Token::OpenBrace,
                 Token::StringLiteral("key".to_string()),
                 Token::Column,
-                Token::Number(1234567890.),
+                Token::Number(JsonNumber::from(1234567890.)),
                 Token::Comma,
                 Token::StringLiteral("key2".to_string()),
                 Token::Column,
@@ -545,7 +1353,7 @@ mod lexer_tests {
     fn should_not_lex_misspelled_false() {
         run_expected_error_test_case_with(
             "{ \"key\": fsale}",
-            JSONError::new(format!("Unexpected 's'"), 1),
+            (JSONErrorKind::UnexpectedChar('s'), 1, 11),
         )
     }
 
@@ -571,7 +1379,7 @@ mod lexer_tests {
     fn should_not_lex_misspelled_true() {
         run_expected_error_test_case_with(
             "{ \"key\": ture}",
-            JSONError::new(format!("Unexpected 'u'"), 1),
+            (JSONErrorKind::UnexpectedChar('u'), 1, 11),
         )
     }
 
@@ -678,7 +1486,7 @@ mod lexer_tests {
     fn should_lex_error_when_null_interrupted_by_space() {
         run_expected_error_test_case_with(
             "{ \"key\": nu ll}",
-            JSONError::new(format!("Unexpected ' '"), 1),
+            (JSONErrorKind::UnexpectedChar(' '), 1, 12),
         )
     }
 
@@ -686,7 +1494,7 @@ mod lexer_tests {
     fn should_lex_error_when_true_interrupted_by_space() {
         run_expected_error_test_case_with(
             "{ \"key\": t  rue}",
-            JSONError::new(format!("Unexpected ' '"), 1),
+            (JSONErrorKind::UnexpectedChar(' '), 1, 11),
         )
     }
 
@@ -694,7 +1502,7 @@ mod lexer_tests {
     fn should_lex_error_when_number_starts_with_zero() {
         run_expected_error_test_case_with(
             "{ \"key\": 011}",
-            JSONError::new(format!("Unexpected '1'"), 1),
+            (JSONErrorKind::InvalidNumber, 1, 11),
         )
     }
 
@@ -706,7 +1514,7 @@ mod lexer_tests {
                 Token::OpenBrace,
                 Token::StringLiteral("key".to_string()),
                 Token::Column,
-                Token::Number(0.),
+                Token::Number(JsonNumber::from(0.)),
                 Token::ClosedBrace,
             ]),
         )
@@ -720,7 +1528,7 @@ mod lexer_tests {
                 Token::OpenBrace,
                 Token::StringLiteral("key".to_string()),
                 Token::Column,
-                Token::Number(1.5),
+                Token::Number(JsonNumber::from(1.5)),
                 Token::ClosedBrace,
             ]),
         )
@@ -734,7 +1542,7 @@ mod lexer_tests {
                 Token::OpenBrace,
                 Token::StringLiteral("key".to_string()),
                 Token::Column,
-                Token::Number(0.2),
+                Token::Number(JsonNumber::from(0.2)),
                 Token::ClosedBrace,
             ]),
         )
@@ -744,7 +1552,7 @@ mod lexer_tests {
     fn should_lex_error_with_decimal_with_multiple_points() {
         run_expected_error_test_case_with(
             "{ \"key\": 0.1.1}",
-            JSONError::new(format!("Unexpected '.'"), 1),
+            (JSONErrorKind::InvalidNumber, 1, 13),
         )
     }
 
@@ -752,7 +1560,7 @@ mod lexer_tests {
     fn should_lex_error_with_decimal_with_multiple_consecutive_points() {
         run_expected_error_test_case_with(
             "{ \"key\": 0..1}",
-            JSONError::new(format!("Unexpected '.'"), 1),
+            (JSONErrorKind::InvalidNumber, 1, 12),
         )
     }
 
@@ -760,7 +1568,7 @@ mod lexer_tests {
     fn should_lex_error_with_zero_followed_by_null() {
         run_expected_error_test_case_with(
             "{ \"key\": 0null}",
-            JSONError::new(format!("Unexpected 'n'"), 1),
+            (JSONErrorKind::UnexpectedChar('n'), 1, 11),
         )
     }
 
@@ -768,7 +1576,7 @@ mod lexer_tests {
     fn should_lex_error_with_zero_followed_by_true() {
         run_expected_error_test_case_with(
             "{ \"key\": 0true}",
-            JSONError::new(format!("Unexpected 't'"), 1),
+            (JSONErrorKind::UnexpectedChar('t'), 1, 11),
         )
     }
 
@@ -776,7 +1584,7 @@ mod lexer_tests {
     fn should_lex_error_with_zero_followed_by_false() {
         run_expected_error_test_case_with(
             "{ \"key\": 0false}",
-            JSONError::new(format!("Unexpected 'f'"), 1),
+            (JSONErrorKind::UnexpectedChar('f'), 1, 11),
         )
     }
 
@@ -784,7 +1592,7 @@ mod lexer_tests {
     fn should_lex_error_with_zero_followed_by_open_bracket() {
         run_expected_error_test_case_with(
             "{ \"key\": 0[}",
-            JSONError::new(format!("Unexpected '['"), 1),
+            (JSONErrorKind::UnexpectedChar('['), 1, 11),
         )
     }
 
@@ -796,7 +1604,7 @@ mod lexer_tests {
                 Token::OpenBrace,
                 Token::StringLiteral("key".to_string()),
                 Token::Column,
-                Token::Number(-0.2),
+                Token::Number(JsonNumber::from(-0.2)),
                 Token::ClosedBrace,
             ]),
         )
@@ -810,7 +1618,7 @@ mod lexer_tests {
                 Token::OpenBrace,
                 Token::StringLiteral("key".to_string()),
                 Token::Column,
-                Token::Number(-0.2),
+                Token::Number(JsonNumber::from(-0.2)),
                 Token::ClosedBrace,
             ]),
         )
@@ -824,7 +1632,7 @@ mod lexer_tests {
                 Token::OpenBrace,
                 Token::StringLiteral("key".to_string()),
                 Token::Column,
-                Token::Number(5.),
+                Token::Number(JsonNumber::from(5.)),
                 Token::ClosedBrace,
             ]),
         )
@@ -838,7 +1646,7 @@ mod lexer_tests {
                 Token::OpenBrace,
                 Token::StringLiteral("key".to_string()),
                 Token::Column,
-                Token::Number(5e10),
+                number("5e10"),
                 Token::ClosedBrace,
             ]),
         )
@@ -852,7 +1660,7 @@ mod lexer_tests {
                 Token::OpenBrace,
                 Token::StringLiteral("key".to_string()),
                 Token::Column,
-                Token::Number(-1.2),
+                Token::Number(JsonNumber::from(-1.2)),
                 Token::ClosedBrace,
             ]),
         )
@@ -866,7 +1674,7 @@ mod lexer_tests {
                 Token::OpenBrace,
                 Token::StringLiteral("key".to_string()),
                 Token::Column,
-                Token::Number(-1.2),
+                Token::Number(JsonNumber::from(-1.2)),
                 Token::ClosedBrace,
             ]),
         )
@@ -880,7 +1688,7 @@ mod lexer_tests {
                 Token::OpenBrace,
                 Token::StringLiteral("key".to_string()),
                 Token::Column,
-                Token::Number(1.2),
+                Token::Number(JsonNumber::from(1.2)),
                 Token::ClosedBrace,
             ]),
         )
@@ -894,7 +1702,7 @@ mod lexer_tests {
                 Token::OpenBrace,
                 Token::StringLiteral("key".to_string()),
                 Token::Column,
-                Token::Number(0.),
+                Token::Number(JsonNumber::from(0.)),
                 Token::ClosedBrace,
             ]),
         )
@@ -908,7 +1716,7 @@ mod lexer_tests {
                 Token::OpenBrace,
                 Token::StringLiteral("key".to_string()),
                 Token::Column,
-                Token::Number(0.),
+                number("0e0"),
                 Token::ClosedBrace,
             ]),
         )
@@ -922,7 +1730,7 @@ mod lexer_tests {
                 Token::OpenBrace,
                 Token::StringLiteral("key".to_string()),
                 Token::Column,
-                Token::Number(0.),
+                number("0E0"),
                 Token::ClosedBrace,
             ]),
         )
@@ -936,7 +1744,7 @@ mod lexer_tests {
                 Token::OpenBrace,
                 Token::StringLiteral("key".to_string()),
                 Token::Column,
-                Token::Number(1e0),
+                number("1e0"),
                 Token::ClosedBrace,
             ]),
         )
@@ -950,7 +1758,7 @@ mod lexer_tests {
                 Token::OpenBrace,
                 Token::StringLiteral("key".to_string()),
                 Token::Column,
-                Token::Number(1e0),
+                number("1e000"),
                 Token::ClosedBrace,
             ]),
         )
@@ -964,7 +1772,7 @@ mod lexer_tests {
                 Token::OpenBrace,
                 Token::StringLiteral("key".to_string()),
                 Token::Column,
-                Token::Number(1.2e0),
+                number("1.2e0"),
                 Token::ClosedBrace,
             ]),
         )
@@ -978,7 +1786,7 @@ mod lexer_tests {
                 Token::OpenBrace,
                 Token::StringLiteral("key".to_string()),
                 Token::Column,
-                Token::Number(1.2e2),
+                number("1.2E2"),
                 Token::ClosedBrace,
             ]),
         )
@@ -992,7 +1800,7 @@ mod lexer_tests {
                 Token::OpenBrace,
                 Token::StringLiteral("key".to_string()),
                 Token::Column,
-                Token::Number(1.2e2),
+                number("1.2e+2"),
                 Token::ClosedBrace,
             ]),
         )
@@ -1006,7 +1814,7 @@ mod lexer_tests {
                 Token::OpenBrace,
                 Token::StringLiteral("key".to_string()),
                 Token::Column,
-                Token::Number(1.2e-10),
+                Token::Number(JsonNumber::from(1.2e-10)),
                 Token::ClosedBrace,
             ]),
         )
@@ -1016,7 +1824,7 @@ mod lexer_tests {
     fn should_lex_error_exponential_decimal_exp_with_sign() {
         run_expected_error_test_case_with(
             "{ \"key\": 1.2E+1.2}",
-            JSONError::new("Unexpected '.'".to_string(), 1),
+            (JSONErrorKind::InvalidNumber, 1, 16),
         )
     }
 
@@ -1024,7 +1832,7 @@ mod lexer_tests {
     fn should_lex_error_exponential_decimal_exp_leading_zero_with_sign() {
         run_expected_error_test_case_with(
             "{ \"key\": 1.2E-0.2}",
-            JSONError::new("Unexpected '.'".to_string(), 1),
+            (JSONErrorKind::InvalidNumber, 1, 16),
         )
     }
 
@@ -1032,7 +1840,7 @@ mod lexer_tests {
     fn should_lex_error_exponential_decimal_exp_leading_zero() {
         run_expected_error_test_case_with(
             "{ \"key\": 1.2E0.2}",
-            JSONError::new("Unexpected '.'".to_string(), 1),
+            (JSONErrorKind::InvalidNumber, 1, 15),
         )
     }
 
@@ -1040,7 +1848,7 @@ mod lexer_tests {
     fn should_lex_error_exponential_decimal_exp() {
         run_expected_error_test_case_with(
             "{ \"key\": 1.2e2.4}",
-            JSONError::new("Unexpected '.'".to_string(), 1),
+            (JSONErrorKind::InvalidNumber, 1, 15),
         )
     }
 
@@ -1048,7 +1856,7 @@ mod lexer_tests {
     fn should_lex_error_with_repeated_exp() {
         run_expected_error_test_case_with(
             "{ \"key\": 1e0e0}",
-            JSONError::new("Unexpected 'e'".to_string(), 1),
+            (JSONErrorKind::InvalidNumber, 1, 13),
         )
     }
 
@@ -1056,7 +1864,7 @@ mod lexer_tests {
     fn should_lex_error_with_plus_followed_by_brace() {
         run_expected_error_test_case_with(
             "{ \"key\": +}",
-            JSONError::new("Unexpected '}'".to_string(), 1),
+            (JSONErrorKind::InvalidNumber, 1, 11),
         )
     }
 
@@ -1064,14 +1872,14 @@ mod lexer_tests {
     fn should_lex_error_with_minus_followed_by_brace() {
         run_expected_error_test_case_with(
             "{ \"key\": -}",
-            JSONError::new("Unexpected '}'".to_string(), 1),
+            (JSONErrorKind::InvalidNumber, 1, 11),
         )
     }
     #[test]
     fn should_lex_error_with_plus_followed_by_comma() {
         run_expected_error_test_case_with(
             "{ \"key\": +,\"\":0}",
-            JSONError::new("Unexpected ','".to_string(), 1),
+            (JSONErrorKind::InvalidNumber, 1, 11),
         )
     }
 
@@ -1079,14 +1887,14 @@ mod lexer_tests {
     fn should_lex_error_with_minus_followed_by_newline() {
         run_expected_error_test_case_with(
             "{ \"key\": -\n}",
-            JSONError::new("Unexpected '\n'".to_string(), 1),
+            (JSONErrorKind::InvalidNumber, 1, 11),
         )
     }
     #[test]
     fn should_lex_error_with_plus_followed_by_newline() {
         run_expected_error_test_case_with(
             "{ \"key\": +\n}",
-            JSONError::new("Unexpected '\n'".to_string(), 1),
+            (JSONErrorKind::InvalidNumber, 1, 11),
         )
     }
 
@@ -1094,7 +1902,7 @@ mod lexer_tests {
     fn should_lex_error_with_minus_followed_by_comma() {
         run_expected_error_test_case_with(
             "{ \"key\": -,\"\":0}",
-            JSONError::new("Unexpected ','".to_string(), 1),
+            (JSONErrorKind::InvalidNumber, 1, 11),
         )
     }
 
@@ -1102,7 +1910,7 @@ mod lexer_tests {
     fn should_lex_error_with_e_followed_by_brace() {
         run_expected_error_test_case_with(
             "{ \"key\": 0e}",
-            JSONError::new("Unexpected '}'".to_string(), 1),
+            (JSONErrorKind::InvalidNumber, 1, 12),
         )
     }
 
@@ -1110,7 +1918,7 @@ mod lexer_tests {
     fn should_lex_error_with_capital_e_followed_by_brace() {
         run_expected_error_test_case_with(
             "{ \"key\": 0E}",
-            JSONError::new("Unexpected '}'".to_string(), 1),
+            (JSONErrorKind::InvalidNumber, 1, 12),
         )
     }
 
@@ -1118,7 +1926,7 @@ mod lexer_tests {
     fn should_lex_error_with_point_followed_by_brace() {
         run_expected_error_test_case_with(
             "{ \"key\": 0.}",
-            JSONError::new("Unexpected '}'".to_string(), 1),
+            (JSONErrorKind::InvalidNumber, 1, 12),
         )
     }
 
@@ -1126,7 +1934,7 @@ mod lexer_tests {
     fn should_lex_error_with_point_followed_by_comma() {
         run_expected_error_test_case_with(
             "{ \"key\": 0.,\"\":0}",
-            JSONError::new("Unexpected ','".to_string(), 1),
+            (JSONErrorKind::InvalidNumber, 1, 12),
         )
     }
 
@@ -1134,7 +1942,7 @@ mod lexer_tests {
     fn should_lex_error_with_point_followed_by_return() {
         run_expected_error_test_case_with(
             "{ \"key\": 0.\n,\"\":0}",
-            JSONError::new("Unexpected '\n'".to_string(), 1),
+            (JSONErrorKind::InvalidNumber, 1, 12),
         )
     }
 
@@ -1142,7 +1950,7 @@ mod lexer_tests {
     fn should_lex_error_with_exp_followed_by_comma() {
         run_expected_error_test_case_with(
             "{ \"key\": 0e,\"\":0}",
-            JSONError::new("Unexpected ','".to_string(), 1),
+            (JSONErrorKind::InvalidNumber, 1, 12),
         )
     }
 
@@ -1150,7 +1958,7 @@ mod lexer_tests {
     fn should_lex_error_with_exp_followed_by_return() {
         run_expected_error_test_case_with(
             "{ \"key\": 0e\n,\"\":0}",
-            JSONError::new("Unexpected '\n'".to_string(), 1),
+            (JSONErrorKind::InvalidNumber, 1, 12),
         )
     }
 
@@ -1158,7 +1966,7 @@ mod lexer_tests {
     fn should_lex_correctly_array_with_zero() {
         run_test_case_with(
             "[0]",
-            Vec::from([Token::OpenBracket, Token::Number(0.), Token::ClosedBracket]),
+            Vec::from([Token::OpenBracket, number("0"), Token::ClosedBracket]),
         )
     }
 
@@ -1166,7 +1974,7 @@ mod lexer_tests {
     fn should_lex_correctly_array_with_one() {
         run_test_case_with(
             "[1]",
-            Vec::from([Token::OpenBracket, Token::Number(1.), Token::ClosedBracket]),
+            Vec::from([Token::OpenBracket, number("1"), Token::ClosedBracket]),
         )
     }
 
@@ -1176,9 +1984,9 @@ mod lexer_tests {
             "[1,2.6]",
             Vec::from([
                 Token::OpenBracket,
-                Token::Number(1.),
+                Token::Number(JsonNumber::from(1.)),
                 Token::Comma,
-                Token::Number(2.6),
+                Token::Number(JsonNumber::from(2.6)),
                 Token::ClosedBracket,
             ]),
         )
@@ -1190,9 +1998,9 @@ mod lexer_tests {
             "[1,2.6e9]",
             Vec::from([
                 Token::OpenBracket,
-                Token::Number(1.),
+                Token::Number(JsonNumber::from(1.)),
                 Token::Comma,
-                Token::Number(2.6e9),
+                number("2.6e9"),
                 Token::ClosedBracket,
             ]),
         )
@@ -1204,37 +2012,44 @@ mod lexer_tests {
             "[1,2.6e0]",
             Vec::from([
                 Token::OpenBracket,
-                Token::Number(1.0),
+                Token::Number(JsonNumber::from(1.0)),
                 Token::Comma,
-                Token::Number(2.6e0),
+                number("2.6e0"),
                 Token::ClosedBracket,
             ]),
         )
     }
 
     #[test]
-    fn should_lex_correctly_very_big_number_as_infinity() {
+    fn should_keep_very_big_number_as_written() {
         run_test_case_with(
             "[1,2.6e1111]",
             Vec::from([
                 Token::OpenBracket,
-                Token::Number(1.0),
+                Token::Number(JsonNumber::from(1.0)),
                 Token::Comma,
-                Token::Number(f64::INFINITY),
+                number("2.6e1111"),
                 Token::ClosedBracket,
             ]),
         )
     }
 
     #[test]
-    fn should_lex_correctly_very_big_negative_number_as_neg_infinity() {
+    fn should_keep_digits_a_f64_cannot_hold() {
+        let tokens = lex("[12345678901234567890, -0.1000000000000000000001]".as_bytes()).unwrap();
+        assert_eq!(number("12345678901234567890"), tokens[1].token);
+        assert_eq!(number("-0.1000000000000000000001"), tokens[3].token);
+    }
+
+    #[test]
+    fn should_keep_very_big_negative_number_as_written() {
         run_test_case_with(
             "[1,-2.6e1111]",
             Vec::from([
                 Token::OpenBracket,
-                Token::Number(1.0),
+                Token::Number(JsonNumber::from(1.0)),
                 Token::Comma,
-                Token::Number(f64::NEG_INFINITY),
+                number("-2.6e1111"),
                 Token::ClosedBracket,
             ]),
         )
@@ -1244,7 +2059,7 @@ mod lexer_tests {
     fn should_error_on_unescaped_tab() {
         run_expected_error_test_case_with(
             "[\"\t\"]",
-            JSONError::new("Unexpected <tab>".to_string(), 1),
+            (JSONErrorKind::ControlCharacter('\t'), 1, 3),
         )
     }
 
@@ -1254,7 +2069,7 @@ mod lexer_tests {
             "[\"\\b\"]",
             Vec::from([
                 Token::OpenBracket,
-                Token::StringLiteral("\\b".to_string()),
+                Token::StringLiteral("\u{08}".to_string()),
                 Token::ClosedBracket,
             ]),
         )
@@ -1266,7 +2081,7 @@ mod lexer_tests {
             "[\"\\f\"]",
             Vec::from([
                 Token::OpenBracket,
-                Token::StringLiteral("\\f".to_string()),
+                Token::StringLiteral("\u{0C}".to_string()),
                 Token::ClosedBracket,
             ]),
         )
@@ -1278,7 +2093,7 @@ mod lexer_tests {
             "[\"\\n\"]",
             Vec::from([
                 Token::OpenBracket,
-                Token::StringLiteral("\\n".to_string()),
+                Token::StringLiteral("\n".to_string()),
                 Token::ClosedBracket,
             ]),
         )
@@ -1290,7 +2105,7 @@ mod lexer_tests {
             "[\"\\r\"]",
             Vec::from([
                 Token::OpenBracket,
-                Token::StringLiteral("\\r".to_string()),
+                Token::StringLiteral("\r".to_string()),
                 Token::ClosedBracket,
             ]),
         )
@@ -1302,7 +2117,7 @@ mod lexer_tests {
             "[\"\\t\"]",
             Vec::from([
                 Token::OpenBracket,
-                Token::StringLiteral("\\t".to_string()),
+                Token::StringLiteral("\t".to_string()),
                 Token::ClosedBracket,
             ]),
         )
@@ -1314,7 +2129,7 @@ mod lexer_tests {
             "[\"\\/\"]",
             Vec::from([
                 Token::OpenBracket,
-                Token::StringLiteral("\\/".to_string()),
+                Token::StringLiteral("/".to_string()),
                 Token::ClosedBracket,
             ]),
         )
@@ -1326,7 +2141,7 @@ mod lexer_tests {
             "[\"\\u0123\"]",
             Vec::from([
                 Token::OpenBracket,
-                Token::StringLiteral("\\u0123".to_string()),
+                Token::StringLiteral("\u{0123}".to_string()),
                 Token::ClosedBracket,
             ]),
         )
@@ -1338,17 +2153,57 @@ mod lexer_tests {
             "[\"\\u12aB\"]",
             Vec::from([
                 Token::OpenBracket,
-                Token::StringLiteral("\\u12aB".to_string()),
+                Token::StringLiteral("\u{12ab}".to_string()),
+                Token::ClosedBracket,
+            ]),
+        )
+    }
+
+    #[test]
+    fn should_lex_surrogate_pair() {
+        run_test_case_with(
+            "[\"a\\ud83d\\ude00b\"]",
+            Vec::from([
+                Token::OpenBracket,
+                Token::StringLiteral("a\u{1F600}b".to_string()),
                 Token::ClosedBracket,
             ]),
         )
     }
 
+    #[test]
+    fn should_error_on_lone_high_surrogate() {
+        run_expected_error_test_case_with(
+            "[\"\\ud83d\"]",
+            (JSONErrorKind::UnpairedSurrogate(0xd83d), 1, 3),
+        )
+    }
+
+    #[test]
+    fn should_error_on_high_surrogate_followed_by_other_escape() {
+        run_expected_error_test_case_with(
+            "[\"\\ud83d\\n\"]",
+            (JSONErrorKind::UnpairedSurrogate(0xd83d), 1, 3),
+        );
+        run_expected_error_test_case_with(
+            "[\"\\ud83d\\u0041\"]",
+            (JSONErrorKind::UnpairedSurrogate(0xd83d), 1, 3),
+        )
+    }
+
+    #[test]
+    fn should_error_on_lone_low_surrogate() {
+        run_expected_error_test_case_with(
+            "[\"a\\uDE00\"]",
+            (JSONErrorKind::UnpairedSurrogate(0xde00), 1, 4),
+        )
+    }
+
     #[test]
     fn should_error_on_invalid_hex() {
         run_expected_error_test_case_with(
             "[\"\\u123z\"]",
-            JSONError::new("Unexpected 'z'".to_string(), 1),
+            (JSONErrorKind::InvalidEscape('z'), 1, 8),
         )
     }
 
@@ -1356,7 +2211,7 @@ mod lexer_tests {
     fn should_error_on_invalid_hex_capital() {
         run_expected_error_test_case_with(
             "[\"\\u123Z\"]",
-            JSONError::new("Unexpected 'Z'".to_string(), 1),
+            (JSONErrorKind::InvalidEscape('Z'), 1, 8),
         )
     }
 
@@ -1364,7 +2219,7 @@ mod lexer_tests {
     fn should_error_on_interrupted_string() {
         run_expected_error_test_case_with(
             "[\"hell",
-            JSONError::new("Unexpected EOF".to_string(), 1),
+            (JSONErrorKind::UnexpectedEof, 1, 7),
         )
     }
 
@@ -1372,7 +2227,7 @@ mod lexer_tests {
     fn should_error_on_interrupted_true() {
         run_expected_error_test_case_with(
             "[tru",
-            JSONError::new("Unexpected EOF".to_string(), 1),
+            (JSONErrorKind::UnexpectedEof, 1, 5),
         )
     }
 
@@ -1380,7 +2235,202 @@ mod lexer_tests {
     fn should_error_on_interrupted_false() {
         run_expected_error_test_case_with(
             "[f",
-            JSONError::new("Unexpected EOF".to_string(), 1),
+            (JSONErrorKind::UnexpectedEof, 1, 3),
         )
     }
+
+    #[test]
+    fn should_yield_tokens_before_error() {
+        let mut lexer = Lexer::new("[1,\n2 x]".as_bytes()).map(|res| res.map(|t| t.token));
+        assert_eq!(Some(Ok(Token::OpenBracket)), lexer.next());
+        assert_eq!(Some(Ok(Token::Number(JsonNumber::from(1.)))), lexer.next());
+        assert_eq!(Some(Ok(Token::Comma)), lexer.next());
+        assert_eq!(Some(Ok(Token::NewLine)), lexer.next());
+        assert_eq!(Some(Ok(Token::Number(JsonNumber::from(2.)))), lexer.next());
+        assert_eq!(
+            Some((JSONErrorKind::UnexpectedChar('x'), 2, 3)),
+            lexer.next().map(|res| describe(&res.unwrap_err()))
+        );
+        assert_eq!(None, lexer.next());
+    }
+
+    #[test]
+    fn should_lex_record_separators_when_asked() {
+        let tokens: Result<Vec<Token>, JSONError> = Lexer::new("\u{1E}1\u{1E}[]".as_bytes())
+            .with_record_separators()
+            .map(|res| res.map(|t| t.token))
+            .collect();
+        assert_eq!(
+            Ok(vec![
+                Token::RecordSeparator,
+                Token::Number(JsonNumber::from(1.)),
+                Token::RecordSeparator,
+                Token::OpenBracket,
+                Token::ClosedBracket,
+            ]),
+            tokens
+        );
+        assert_eq!(
+            Err((JSONErrorKind::UnexpectedChar('\u{1E}'), 1, 1)),
+            lex_classic("\u{1E}[]")
+        );
+    }
+
+    fn lex_classic(input: &str) -> Result<Vec<Token>, (JSONErrorKind, usize, usize)> {
+        lex(input.as_bytes())
+            .map(|tokens| tokens.into_iter().map(|spanned| spanned.token).collect())
+            .map_err(|err| describe(&err))
+    }
+
+    fn lex_json5(input: &str) -> Result<Vec<Token>, (JSONErrorKind, usize, usize)> {
+        let options = ParserOptions::new().dialect(Dialect::Json5);
+        Lexer::with_options(input.as_bytes(), &options)
+            .map(|res| res.map(|spanned| spanned.token))
+            .collect::<Result<Vec<Token>, JSONError>>()
+            .map_err(|err| describe(&err))
+    }
+
+    #[test]
+    fn should_join_json5_line_continuations() {
+        assert_eq!(
+            Ok(vec![Token::StringLiteral("two lines".to_string())]),
+            lex_json5("'two \\\nlines'")
+        );
+        for continuation in ["\r\n", "\r", "\u{2028}", "\u{2029}"] {
+            assert_eq!(
+                Ok(vec![Token::StringLiteral("two lines".to_string())]),
+                lex_json5(&format!("'two \\{}lines'", continuation))
+            );
+        }
+        assert_eq!(
+            Err((JSONErrorKind::InvalidEscape('\n'), 1, 7)),
+            lex_classic("\"two \\\nlines\"")
+        );
+    }
+
+    #[test]
+    fn should_skip_json5_comments() {
+        assert_eq!(
+            Ok(vec![
+                Token::OpenBracket,
+                Token::Number(JsonNumber::from(1.)),
+                Token::Comma,
+                Token::NewLine,
+                Token::Number(JsonNumber::from(2.)),
+                Token::Number(JsonNumber::from(3.)),
+                Token::ClosedBracket,
+            ]),
+            lex_json5("[1, // one\n /* two\n lines */ 2/**/3]")
+        );
+        for line_break in ["\r", "\r\n", "\u{2028}", "\u{2029}"] {
+            let input = format!("[1, // one{}2]", line_break);
+            let tokens = lex_json5(&input).map(|tokens| {
+                tokens
+                    .into_iter()
+                    .filter(|token| *token != Token::NewLine)
+                    .collect::<Vec<_>>()
+            });
+            assert_eq!(
+                Ok(vec![
+                    Token::OpenBracket,
+                    Token::Number(JsonNumber::from(1.)),
+                    Token::Comma,
+                    Token::Number(JsonNumber::from(2.)),
+                    Token::ClosedBracket,
+                ]),
+                tokens,
+                "{:?}",
+                line_break
+            );
+        }
+        assert_eq!(
+            Err((JSONErrorKind::UnexpectedEof, 2, 5)),
+            lex_json5("[/* never\nends")
+        );
+        assert_eq!(
+            Err((JSONErrorKind::UnexpectedChar('/'), 1, 4)),
+            lex_classic("[1 // one")
+        );
+    }
+
+    #[test]
+    fn should_lex_json5_strings() {
+        assert_eq!(
+            Ok(vec![
+                Token::OpenBracket,
+                Token::StringLiteral("a\"b".to_string()),
+                Token::Comma,
+                Token::StringLiteral("c'd".to_string()),
+                Token::Comma,
+                Token::StringLiteral("'A\u{0B}\0a\t".to_string()),
+                Token::ClosedBracket,
+            ]),
+            lex_json5("['a\"b', \"c'd\", '\\'\\x41\\v\\0\\a\t']")
+        );
+        assert_eq!(
+            Err((JSONErrorKind::InvalidEscape('1'), 1, 4)),
+            lex_json5("'\\01'")
+        );
+    }
+
+    #[test]
+    fn should_lex_json5_identifiers_and_keywords() {
+        assert_eq!(
+            Ok(vec![
+                Token::OpenBrace,
+                Token::Identifier("$key_1".to_string()),
+                Token::Column,
+                Token::BoolTrue,
+                Token::Comma,
+                Token::Identifier("ünï".to_string()),
+                Token::Column,
+                Token::Null,
+                Token::ClosedBrace,
+            ]),
+            lex_json5("{$key_1:true, ünï: null}")
+        );
+        assert_eq!(
+            Err((JSONErrorKind::InvalidNumber, 1, 2)),
+            lex_json5("[-Infinit]")
+        );
+    }
+
+    #[test]
+    fn should_lex_json5_numbers() {
+        assert_eq!(
+            Ok(vec![
+                Token::OpenBracket,
+                Token::Number(JsonNumber::from(31.)),
+                Token::Number(JsonNumber::from(-255.)),
+                Token::Number(JsonNumber::from(0.5)),
+                Token::Number(JsonNumber::from(5.)),
+                Token::Number(JsonNumber::from(-0.5)),
+                number("5e1"),
+                Token::Number(JsonNumber::from(f64::INFINITY)),
+                Token::Number(JsonNumber::from(f64::NEG_INFINITY)),
+                Token::Number(JsonNumber::from(1.)),
+                Token::ClosedBracket,
+            ]),
+            lex_json5("[0x1F -0XfF .5 5. -.5 5.e1 Infinity -Infinity +1]")
+        );
+        match lex_json5("NaN").unwrap()[..] {
+            [Token::Number(ref n)] => assert!(n.as_f64().is_nan()),
+            ref tokens => panic!("unexpected tokens {:?}", tokens),
+        }
+        assert_eq!(Err((JSONErrorKind::InvalidNumber, 1, 4)), lex_json5("[0xg]"));
+    }
+
+    #[test]
+    fn should_accept_unicode_whitespace_in_json5() {
+        assert_eq!(
+            Ok(vec![
+                Token::OpenBracket,
+                Token::Number(JsonNumber::from(1.)),
+                Token::Comma,
+                Token::Number(JsonNumber::from(2.)),
+                Token::ClosedBracket,
+            ]),
+            lex_json5("\u{FEFF}[1,\u{A0}2\u{2028}\u{0B}\u{0C}]")
+        );
+    }
 }
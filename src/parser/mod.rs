@@ -1,23 +1,153 @@
-use std::io::BufRead;
+use std::{
+    io::{self, BufRead, Write},
+    mem,
+};
+
+use crate::serializer::{write_string, write_token};
 
 mod token;
-use lexer::lex;
-use parser::parse;
+pub use token::{Position, Span, SpannedToken, Token};
 
 mod error;
-use error::JSONError;
+pub use error::{IoError, JSONError, JSONErrorKind};
+
+mod number;
+pub use number::JsonNumber;
+
+mod value;
+pub use value::{JsonValue, ValueBuilder};
+
+mod events;
+pub use events::{parse_events, visit, Events, JsonEvent, JsonVisitor};
+
+mod options;
+pub use options::{
+    AllowedEncodings, AllowedRoots, Dialect, DuplicateKeys, InvalidUtf8, ParserOptions,
+    DEFAULT_VALUE_DEPTH,
+};
+
+mod encoding;
+pub use encoding::Encoding;
 
 mod lexer;
+pub use lexer::{lex, Lexer};
+
+#[allow(clippy::module_inception)]
 mod parser;
+use parser::{parse, parse_with, State};
+
+mod documents;
+pub use documents::{parse_documents, Documents};
+
+mod push;
+pub use push::PushParser;
+
+mod ndjson;
+pub use ndjson::{check_ndjson, NdjsonLines};
+
+#[cfg(test)]
+mod test_utils;
+
+#[cfg(test)]
+mod conformance_tests;
 
 pub fn check_valid<R: BufRead>(reader: R) -> Result<(), JSONError> {
-    let tokens = lex(reader)?;
-    parse(tokens)
+    check_valid_with(reader, &ParserOptions::default())
+}
+
+pub fn check_valid_with<R: BufRead>(reader: R, options: &ParserOptions) -> Result<(), JSONError> {
+    parse(Lexer::with_options(reader, options), options)
+}
+
+pub fn parse_value<R: BufRead>(reader: R) -> Result<JsonValue, JSONError> {
+    parse_value_with(reader, &ParserOptions::default())
+}
+
+// unless set, `max_depth` is `DEFAULT_VALUE_DEPTH`
+pub fn parse_value_with<R: BufRead>(
+    reader: R,
+    options: &ParserOptions,
+) -> Result<JsonValue, JSONError> {
+    parse_value_with_visitor(reader, options, &mut ())
+}
+
+// the same, calling `visitor` back on the way like `visit` does
+pub fn parse_value_with_visitor<R: BufRead, V: JsonVisitor>(
+    reader: R,
+    options: &ParserOptions,
+    visitor: &mut V,
+) -> Result<JsonValue, JSONError> {
+    let options = options.for_values();
+    let mut visitors = (ValueBuilder::with_options(&options), visitor);
+    parse_with(Lexer::with_options(reader, &options), &options, &mut visitors)?;
+    Ok(visitors
+        .0
+        .finish()
+        .expect("a successful parse always produces a root value"))
+}
+
+pub fn minify<R: BufRead, W: Write>(reader: R, writer: W) -> Result<(), JSONError> {
+    minify_with(reader, writer, &ParserOptions::default())
+}
+
+// copies the document to `writer` without insignificant whitespace, one token
+// at a time; on error the output stops right before the token in error, so it
+// only holds what the parser accepted
+pub fn minify_with<R: BufRead, W: Write>(
+    reader: R,
+    writer: W,
+    options: &ParserOptions,
+) -> Result<(), JSONError> {
+    minify_with_visitor(reader, writer, options, &mut ())
+}
+
+// the same, calling `visitor` back on the way like `visit` does
+pub fn minify_with_visitor<R: BufRead, W: Write, V: JsonVisitor>(
+    reader: R,
+    mut writer: W,
+    options: &ParserOptions,
+    visitor: &mut V,
+) -> Result<(), JSONError> {
+    let io_error = |e: io::Error, span| JSONError::new(JSONErrorKind::Io(e.into()), span);
+    let mut state = State::new(options);
+    let mut end = Position::start();
+    let mut comma = false;
+    // a token is only written once the parser accepted it
+    let mut accepted = Vec::new();
+    for token in Lexer::with_options(reader, options) {
+        let token = token?;
+        end = token.span.end;
+        let span = token.span;
+        accepted.clear();
+        match token.token {
+            Token::NewLine => {}
+            Token::Comma => comma = true,
+            _ => {
+                // commas are written once they are known not to be JSON5
+                // trailing commas, so that the output is always plain JSON
+                let closing = matches!(token.token, Token::ClosedBrace | Token::ClosedBracket);
+                if mem::take(&mut comma) && !closing {
+                    accepted.push(b',');
+                }
+                match state.keyword_key(&token.token, span) {
+                    Some(key) => write_string(&mut accepted, &key),
+                    None => write_token(&mut accepted, &token.token),
+                }
+                .expect("writing to a Vec cannot fail");
+            }
+        }
+        state.step(token, visitor)?;
+        writer.write_all(&accepted).map_err(|e| io_error(e, span))?;
+    }
+    writer.flush().map_err(|e| io_error(e, Span::at(end)))?;
+    state.finish(end)
 }
 
 #[cfg(test)]
 mod check_valid_tests {
-    use crate::parser::check_valid;
+    use crate::parser::{
+        check_valid, check_valid_with, AllowedRoots, Dialect, DuplicateKeys, ParserOptions,
+    };
 
     #[test]
     fn should_not_report_error_for_obj() {
@@ -28,14 +158,14 @@ mod check_valid_tests {
     #[test]
     fn should_report_error_for_not_closed_brace() {
         let found_err = check_valid("{".as_bytes()).unwrap_err();
-        assert_eq!("Unexpected EOF: at line 1", found_err.to_string())
+        assert_eq!("Unexpected EOF: at line 1, column 2", found_err.to_string())
     }
 
     #[test]
     fn should_report_error_for_closed_brace_outside_obj() {
         let found_err = check_valid("}".as_bytes()).unwrap_err();
         assert_eq!(
-            "Unexpected '}': at line 1",
+            "Unexpected '}', expected '{' or '[': at line 1, column 1",
             found_err.to_string()
         )
     }
@@ -43,13 +173,13 @@ mod check_valid_tests {
     #[test]
     fn should_report_error_for_random_char() {
         let found_err = check_valid("{a".as_bytes()).unwrap_err();
-        assert_eq!("Unexpected 'a': at line 1", found_err.to_string())
+        assert_eq!("Unexpected 'a': at line 1, column 2", found_err.to_string())
     }
 
     #[test]
     fn should_report_unexpected_eof_for_empty_file() {
         let found_err = check_valid("".as_bytes()).unwrap_err();
-        assert_eq!("Unexpected EOF: at line 1", found_err.to_string())
+        assert_eq!("Unexpected EOF: at line 1, column 1", found_err.to_string())
     }
 
     #[test]
@@ -106,9 +236,472 @@ mod check_valid_tests {
         assert_eq!(Ok(()), res)
     }
 
+    #[test]
+    fn should_report_line_and_column_of_error() {
+        let found_err = check_valid("{\n  \"key\": 1,\n  \"key\" 2\n}".as_bytes()).unwrap_err();
+        assert_eq!(
+            "Unexpected '<number>', expected ':' after the key: at line 3, column 9",
+            found_err.to_string()
+        );
+        assert_eq!(22, found_err.offset());
+    }
+
+    #[test]
+    fn should_report_eof_after_last_token() {
+        let found_err = check_valid("[\n  1,\n  2\n".as_bytes()).unwrap_err();
+        assert_eq!("Unexpected EOF: at line 4, column 1", found_err.to_string())
+    }
+
     #[test]
     fn should_recognize_nested_objects() {
         let res = check_valid("{ \"key\": {\n\"inner_key\":\"inner_val\"\n}\n}".as_bytes());
         assert_eq!(Ok(()), res)
     }
+
+    #[test]
+    fn should_report_unpaired_surrogate() {
+        let found_err = check_valid("[\"\\ud83d x\"]".as_bytes()).unwrap_err();
+        assert_eq!(
+            "Unpaired surrogate '\\ud83d': at line 1, column 3",
+            found_err.to_string()
+        )
+    }
+
+    #[test]
+    fn should_only_accept_scalar_root_in_strict_dialect() {
+        let strict = ParserOptions::new().dialect(Dialect::Strict);
+        let found_err = check_valid("42".as_bytes()).unwrap_err();
+        assert_eq!(
+            "Unexpected '<number>', expected '{' or '[': at line 1, column 1",
+            found_err.to_string()
+        );
+        assert_eq!(Ok(()), check_valid_with("42".as_bytes(), &strict));
+
+        let found_err = check_valid_with("]".as_bytes(), &strict).unwrap_err();
+        assert_eq!(
+            "Unexpected ']', expected a value: at line 1, column 1",
+            found_err.to_string()
+        );
+    }
+
+    #[test]
+    fn should_only_accept_plus_sign_in_classic_dialect() {
+        let strict = ParserOptions::new().dialect(Dialect::Strict);
+        assert_eq!(Ok(()), check_valid("[+1]".as_bytes()));
+        let found_err = check_valid_with("[+1]".as_bytes(), &strict).unwrap_err();
+        assert_eq!("Unexpected '+': at line 1, column 2", found_err.to_string());
+    }
+
+    #[test]
+    fn should_restrict_root_to_object() {
+        let options = ParserOptions::new().allowed_roots(AllowedRoots::Object);
+        assert_eq!(Ok(()), check_valid_with("{}".as_bytes(), &options));
+        let found_err = check_valid_with("[]".as_bytes(), &options).unwrap_err();
+        assert_eq!(
+            "Unexpected '[', expected '{': at line 1, column 1",
+            found_err.to_string()
+        );
+    }
+
+    #[test]
+    fn should_allow_any_root_in_classic_dialect_when_asked() {
+        let options = ParserOptions::new().allowed_roots(AllowedRoots::Any);
+        assert_eq!(Ok(()), check_valid_with("null".as_bytes(), &options));
+    }
+
+    #[test]
+    fn should_report_duplicate_keys_when_asked() {
+        let input = "{\"a\": {\"a\": 1, \"b\": 2}, \"b\": 3, \"a\": 4}";
+        assert_eq!(Ok(()), check_valid(input.as_bytes()));
+        let options = ParserOptions::new().duplicate_keys(DuplicateKeys::Error);
+        let found_err = check_valid_with(input.as_bytes(), &options).unwrap_err();
+        assert_eq!(
+            "Duplicate key \"a\": at line 1, column 33",
+            found_err.to_string()
+        );
+    }
+
+    #[test]
+    fn should_limit_nesting_depth() {
+        let options = ParserOptions::new().max_depth(3);
+        assert_eq!(Ok(()), check_valid_with("[{\"a\": []}, []]".as_bytes(), &options));
+        let found_err = check_valid_with("[{\"a\": [[]]}]".as_bytes(), &options).unwrap_err();
+        assert_eq!(
+            "Nesting deeper than 3 levels: at line 1, column 9",
+            found_err.to_string()
+        );
+    }
+
+    #[test]
+    fn should_limit_string_and_number_lengths() {
+        let options = ParserOptions::new().max_string_length(3).max_number_length(4);
+        let input = "{\"abc\": [\"\\u00e9e\", -1.5, 1e10]}";
+        assert_eq!(Ok(()), check_valid_with(input.as_bytes(), &options));
+        let found_err = check_valid_with("[\"abcd\"]".as_bytes(), &options).unwrap_err();
+        assert_eq!(
+            "String longer than 3 bytes: at line 1, column 2",
+            found_err.to_string()
+        );
+        let found_err = check_valid_with("[1, -1.25]".as_bytes(), &options).unwrap_err();
+        assert_eq!(
+            "Number longer than 4 characters: at line 1, column 5",
+            found_err.to_string()
+        );
+        let options = options.dialect(Dialect::Json5);
+        let found_err = check_valid_with("{abcd: 1}".as_bytes(), &options).unwrap_err();
+        assert_eq!(
+            "String longer than 3 bytes: at line 1, column 2",
+            found_err.to_string()
+        );
+    }
+
+    #[test]
+    fn should_limit_input_size() {
+        let options = ParserOptions::new().max_size(8);
+        assert_eq!(Ok(()), check_valid_with("[1, 2]  ".as_bytes(), &options));
+        let found_err = check_valid_with("[1, 2]   ".as_bytes(), &options).unwrap_err();
+        assert_eq!(
+            "Input larger than 8 bytes: at line 1, column 9",
+            found_err.to_string()
+        );
+    }
+
+    #[test]
+    fn should_limit_members() {
+        let options = ParserOptions::new().max_members(2);
+        let input = "{\"a\": [1, {}], \"b\": [[], \"x\"]}";
+        assert_eq!(Ok(()), check_valid_with(input.as_bytes(), &options));
+        let found_err = check_valid_with("[1, [2, 3], 4]".as_bytes(), &options).unwrap_err();
+        assert_eq!(
+            "Object or array with more than 2 members: at line 1, column 13",
+            found_err.to_string()
+        );
+        let input = "{\"a\": 1, \"b\": 2, \"c\": {}}";
+        let found_err = check_valid_with(input.as_bytes(), &options).unwrap_err();
+        assert_eq!(
+            "Object or array with more than 2 members: at line 1, column 23",
+            found_err.to_string()
+        );
+    }
+
+    #[test]
+    fn should_stop_reading_at_first_error() {
+        // the first line is read lazily, so the parser rejects it before the
+        // second line is ever reached
+        struct Lines(Vec<&'static str>);
+        impl std::io::Read for Lines {
+            fn read(&mut self, _: &mut [u8]) -> std::io::Result<usize> {
+                unreachable!("read through BufRead only")
+            }
+        }
+        impl std::io::BufRead for Lines {
+            fn fill_buf(&mut self) -> std::io::Result<&[u8]> {
+                match self.0.first() {
+                    Some(line) => Ok(line.as_bytes()),
+                    None => panic!("read past the first error"),
+                }
+            }
+            fn consume(&mut self, amt: usize) {
+                assert_eq!(self.0[0].len(), amt);
+                self.0.remove(0);
+            }
+        }
+
+        let found_err = check_valid(Lines(vec!["[1]]\n", "[2]\n"])).unwrap_err();
+        assert_eq!(
+            "Unexpected ']', expected the end of the document: at line 1, column 4",
+            found_err.to_string()
+        );
+    }
+}
+
+#[cfg(test)]
+mod parse_value_tests {
+    use crate::serializer::to_string;
+    use crate::parser::{
+        check_valid, parse_documents, parse_value, parse_value_with, DuplicateKeys,
+        JSONErrorKind, JsonNumber, JsonValue, ParserOptions, DEFAULT_VALUE_DEPTH,
+    };
+
+    #[test]
+    fn should_parse_empty_obj() {
+        let res = parse_value("{}".as_bytes());
+        assert_eq!(Ok(JsonValue::Object(vec![])), res);
+    }
+
+    #[test]
+    fn should_parse_obj_with_every_kind_of_value() {
+        let value = parse_value(
+            "{\"s\": \"val\",\n \"n\": -1.5e2, \"t\": true, \"f\": false, \"z\": null}\n".as_bytes(),
+        )
+        .unwrap();
+        assert_eq!(Some("val"), value.get("s").and_then(JsonValue::as_str));
+        assert_eq!(Some(-150.), value.get("n").and_then(JsonValue::as_f64));
+        assert_eq!(Some(true), value.get("t").and_then(JsonValue::as_bool));
+        assert_eq!(Some(false), value.get("f").and_then(JsonValue::as_bool));
+        assert!(value.get("z").unwrap().is_null());
+    }
+
+    #[test]
+    fn should_parse_array_of_objects() {
+        let res = parse_value("[{\"a\": [1, 2]}, {}, []]".as_bytes());
+        assert_eq!(
+            Ok(JsonValue::Array(vec![
+                JsonValue::Object(vec![(
+                    "a".to_string(),
+                    JsonValue::Array(vec![
+                        JsonValue::Number(JsonNumber::from(1.)),
+                        JsonValue::Number(JsonNumber::from(2.))
+                    ])
+                )]),
+                JsonValue::Object(vec![]),
+                JsonValue::Array(vec![]),
+            ])),
+            res
+        );
+    }
+
+    #[test]
+    fn should_report_error_for_invalid_document() {
+        let found_err = parse_value("[1,]".as_bytes()).unwrap_err();
+        assert_eq!("Unexpected ']', expected a value after ',': at line 1, column 4", found_err.to_string())
+    }
+
+    #[test]
+    fn should_keep_one_member_per_key_when_asked() {
+        let input = "{\"a\": 1, \"b\": {\"a\": 2}, \"a\": [3], \"c\": 4, \"a\": 5}";
+        let members = |policy| {
+            let options = ParserOptions::new().duplicate_keys(policy);
+            match parse_value_with(input.as_bytes(), &options).unwrap() {
+                JsonValue::Object(members) => members
+                    .into_iter()
+                    .map(|(key, value)| format!("{}={}", key, to_string(&value)))
+                    .collect::<Vec<String>>()
+                    .join(" "),
+                value => panic!("unexpected value {:?}", value),
+            }
+        };
+        assert_eq!("a=1 b={\"a\":2} c=4", members(DuplicateKeys::FirstWins));
+        assert_eq!("a=5 b={\"a\":2} c=4", members(DuplicateKeys::LastWins));
+        assert_eq!("a=1 b={\"a\":2} a=[3] c=4 a=5", members(DuplicateKeys::Warn));
+    }
+
+    #[test]
+    fn should_limit_the_depth_of_built_values() {
+        let nested = |depth: usize| format!("{}{}", "[".repeat(depth), "]".repeat(depth));
+        let input = nested(DEFAULT_VALUE_DEPTH);
+        let value = parse_value(input.as_bytes()).unwrap();
+        assert_eq!(input, to_string(&value));
+        assert_eq!(value, value.clone());
+
+        let too_deep = nested(DEFAULT_VALUE_DEPTH + 1);
+        let err = parse_value(too_deep.as_bytes()).unwrap_err();
+        assert_eq!(
+            JSONErrorKind::DepthLimitExceeded(DEFAULT_VALUE_DEPTH),
+            *err.kind()
+        );
+        let err = parse_documents(too_deep.as_bytes(), &ParserOptions::new())
+            .next()
+            .unwrap()
+            .unwrap_err();
+        assert_eq!(
+            JSONErrorKind::DepthLimitExceeded(DEFAULT_VALUE_DEPTH),
+            *err.kind()
+        );
+        // validating without building a value has no limit unless one is set
+        assert_eq!(Ok(()), check_valid(too_deep.as_bytes()));
+
+        let options = ParserOptions::new().max_depth(1000);
+        assert!(parse_value_with(nested(1000).as_bytes(), &options).is_ok());
+    }
+}
+
+#[cfg(test)]
+mod minify_tests {
+    use std::{
+        collections::VecDeque,
+        io::{self, Write},
+    };
+
+    use crate::parser::{
+        minify, minify_with_visitor, JSONErrorKind, JsonEvent, JsonValue, ParserOptions,
+    };
+
+    fn minified(input: &str) -> Result<String, String> {
+        let mut out = Vec::new();
+        minify(input.as_bytes(), &mut out).map_err(|e| e.to_string())?;
+        Ok(String::from_utf8(out).unwrap())
+    }
+
+    #[test]
+    fn should_remove_whitespace() {
+        assert_eq!(
+            Ok("{\"a\":[1,2.5,true,false,null],\"b c\":{}}".to_string()),
+            minified("{\n  \"a\": [ 1, 2.5, true, false, null ],\n  \"b c\" : {}\n}\n")
+        );
+    }
+
+    #[test]
+    fn should_report_error_for_invalid_document() {
+        assert_eq!(
+            Err("Unexpected ']', expected a value after ',': at line 2, column 3".to_string()),
+            minified("[1,\n  ]")
+        );
+        assert_eq!(
+            Err("Unexpected EOF: at line 1, column 4".to_string()),
+            minified("[1, ")
+        );
+    }
+
+    #[test]
+    fn should_only_write_accepted_tokens() {
+        for (input, written) in [("[1 2]", "[1"), ("{\"a\": 1,,", "{\"a\":1"), ("[1,]", "[1")] {
+            let mut out = Vec::new();
+            assert!(minify(input.as_bytes(), &mut out).is_err());
+            assert_eq!(written, String::from_utf8(out).unwrap());
+        }
+    }
+
+    #[test]
+    fn should_keep_escaped_characters() {
+        assert_eq!(
+            Ok("[\"a\\nb\\\"c\\\\/😀é\"]".to_string()),
+            minified("[\"a\\nb\\\"c\\\\\\/\\ud83d\\ude00\\u00e9\"]")
+        );
+    }
+
+    struct FailingWriter;
+
+    impl Write for FailingWriter {
+        fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+            Err(io::Error::new(io::ErrorKind::BrokenPipe, "closed"))
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn should_report_write_errors() {
+        let err = minify("[1]".as_bytes(), FailingWriter).unwrap_err();
+        match err.kind() {
+            JSONErrorKind::Io(e) => assert_eq!(io::ErrorKind::BrokenPipe, e.get_ref().kind()),
+            kind => panic!("unexpected error kind {:?}", kind),
+        }
+    }
+
+    #[test]
+    fn should_call_the_visitor_back() {
+        let (mut out, mut events) = (Vec::new(), VecDeque::new());
+        let input = "{\"a\": [1]}".as_bytes();
+        minify_with_visitor(input, &mut out, &ParserOptions::new(), &mut events).unwrap();
+        assert_eq!(b"{\"a\":[1]}", &out[..]);
+        assert_eq!(
+            vec![
+                JsonEvent::StartObject,
+                JsonEvent::Key("a".to_string()),
+                JsonEvent::StartArray,
+                JsonEvent::Value(JsonValue::Number(1.0.into())),
+                JsonEvent::EndArray,
+                JsonEvent::EndObject,
+            ],
+            Vec::from(events)
+        );
+    }
+}
+
+#[cfg(test)]
+mod json5_tests {
+    use crate::parser::{
+        check_valid_with, minify_with, parse_value_with, Dialect, JsonNumber, JsonValue,
+        ParserOptions,
+    };
+
+    fn json5() -> ParserOptions {
+        ParserOptions::new().dialect(Dialect::Json5)
+    }
+
+    #[test]
+    fn should_parse_config_file() {
+        let input = "// settings\n\
+                     {\n\
+                       name: 'server', /* the default */\n\
+                       port: 0x1F90,\n\
+                       ratio: .5,\n\
+                       hosts: ['a', 'b',],\n\
+                     }\n";
+        assert_eq!(
+            Ok(JsonValue::Object(vec![
+                ("name".to_string(), JsonValue::String("server".to_string())),
+                ("port".to_string(), JsonValue::Number(JsonNumber::from(8080.))),
+                ("ratio".to_string(), JsonValue::Number(JsonNumber::from(0.5))),
+                (
+                    "hosts".to_string(),
+                    JsonValue::Array(vec![
+                        JsonValue::String("a".to_string()),
+                        JsonValue::String("b".to_string())
+                    ])
+                ),
+            ])),
+            parse_value_with(input.as_bytes(), &json5())
+        );
+    }
+
+    #[test]
+    fn should_accept_scalar_root() {
+        assert_eq!(Ok(()), check_valid_with("'a' // text".as_bytes(), &json5()));
+    }
+
+    #[test]
+    fn should_reject_identifier_values() {
+        let found_err = check_valid_with("{a: b}".as_bytes(), &json5()).unwrap_err();
+        assert_eq!(
+            "Unexpected '<identifier>', expected a value after ':': at line 1, column 5",
+            found_err.to_string()
+        );
+    }
+
+    #[test]
+    fn should_only_allow_one_trailing_comma() {
+        let found_err = check_valid_with("[1,,]".as_bytes(), &json5()).unwrap_err();
+        assert_eq!(
+            "Unexpected ',', expected a value after ',': at line 1, column 4",
+            found_err.to_string()
+        );
+        assert!(check_valid_with("{,}".as_bytes(), &json5()).is_err());
+    }
+
+    #[test]
+    fn should_minify_to_plain_json() {
+        let mut out = Vec::new();
+        minify_with(
+            "{a: [1, Infinity, 0x10,], 'b': 'x',} // end".as_bytes(),
+            &mut out,
+            &json5(),
+        )
+        .unwrap();
+        assert_eq!("{\"a\":[1,null,16],\"b\":\"x\"}", String::from_utf8(out).unwrap());
+    }
+
+    #[test]
+    fn should_accept_words_read_as_values_as_keys() {
+        let input = "{true: 1, false: 2, null: 3, Infinity: 4, NaN: 5}";
+        let value = parse_value_with(input.as_bytes(), &json5()).unwrap();
+        let keys: Vec<&str> = value.as_object().unwrap().iter().map(|(k, _)| k.as_str()).collect();
+        assert_eq!(vec!["true", "false", "null", "Infinity", "NaN"], keys);
+        let mut out = Vec::new();
+        minify_with("{null: [null]}".as_bytes(), &mut out, &json5()).unwrap();
+        assert_eq!("{\"null\":[null]}", String::from_utf8(out).unwrap());
+        assert!(check_valid_with("{+Infinity: 1}".as_bytes(), &json5()).is_err());
+        assert!(check_valid_with("{-NaN: 1}".as_bytes(), &json5()).is_err());
+        assert!(check_valid_with("{true: 1}".as_bytes(), &ParserOptions::new()).is_err());
+    }
+
+    #[test]
+    fn should_reject_json5_in_other_dialects() {
+        assert!(check_valid_with("[1,]".as_bytes(), &ParserOptions::new()).is_err());
+        let strict = ParserOptions::new().dialect(Dialect::Strict);
+        assert!(check_valid_with("{'a': 1}".as_bytes(), &strict).is_err());
+    }
 }
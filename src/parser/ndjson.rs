@@ -0,0 +1,430 @@
+use std::io::{self, BufRead, Read};
+
+use super::{
+    encoding::Encoding,
+    error::{JSONError, JSONErrorKind},
+    events::JsonVisitor,
+    lexer::Lexer,
+    options::ParserOptions,
+    parser::parse_with,
+    token::{Position, Span},
+};
+
+// the bytes of the next line of `inner`, without its "\n" or "\r\n", read as
+// the lexer asks for them so that its limits hold before the line is over
+struct Line<'a, R> {
+    inner: &'a mut R,
+    start: Position,
+    max_size: Option<usize>,
+    // how many bytes of `inner` the line took, its line break included
+    read: usize,
+    // whether every byte so far is a space or a tab
+    blank: bool,
+    // a "\r" that ended a buffer of `inner`, already consumed from it, which
+    // is only part of the line if no "\n" comes next
+    carriage_return: bool,
+    done: bool,
+}
+
+impl<'a, R: BufRead> Line<'a, R> {
+    fn new(inner: &'a mut R, start: Position, options: &ParserOptions) -> Line<'a, R> {
+        Line {
+            inner,
+            start,
+            max_size: options.max_size,
+            read: 0,
+            blank: true,
+            carriage_return: false,
+            done: false,
+        }
+    }
+
+    fn skip(&mut self, amt: usize) {
+        self.inner.consume(amt);
+        self.read += amt;
+    }
+
+    // reads what the lexer left of the line without keeping it, as long as
+    // the input stays under its size limit
+    fn drain(&mut self) -> Result<(), JSONError> {
+        let span = Span::at(self.start);
+        loop {
+            let amt = match self.fill_buf() {
+                Ok(buf) => buf.len(),
+                Err(err) => return Err(JSONError::new(JSONErrorKind::Io(err.into()), span)),
+            };
+            if amt == 0 {
+                return Ok(());
+            }
+            self.consume(amt);
+            let size = self.start.offset + self.read;
+            if let Some(limit) = self.max_size.filter(|limit| size > *limit) {
+                return Err(JSONError::new(
+                    JSONErrorKind::SizeLimitExceeded(limit),
+                    span,
+                ));
+            }
+        }
+    }
+}
+
+impl<R: BufRead> Read for Line<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let available = self.fill_buf()?;
+        let amt = available.len().min(buf.len());
+        buf[..amt].copy_from_slice(&available[..amt]);
+        self.consume(amt);
+        Ok(amt)
+    }
+}
+
+impl<R: BufRead> BufRead for Line<'_, R> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        while !self.done {
+            let buf = self.inner.fill_buf()?;
+            if self.carriage_return {
+                if buf.first() != Some(&b'\n') {
+                    return Ok(b"\r");
+                }
+                self.carriage_return = false;
+                self.skip(1);
+                self.done = true;
+                continue;
+            }
+            let (len, line_break) = match buf.iter().position(|b| *b == b'\n') {
+                Some(newline) if buf[..newline].ends_with(b"\r") => (newline - 1, 2),
+                Some(newline) => (newline, 1),
+                // the "\n" can be at the start of the next buffer
+                None if buf.ends_with(b"\r") => (buf.len() - 1, 0),
+                None => (buf.len(), 0),
+            };
+            let at_end = buf.is_empty();
+            if len > 0 {
+                return Ok(&self.inner.fill_buf()?[..len]);
+            }
+            if line_break > 0 {
+                self.skip(line_break);
+                self.done = true;
+            } else if at_end {
+                self.done = true;
+            } else {
+                self.skip(1);
+                self.carriage_return = true;
+            }
+        }
+        Ok(&[])
+    }
+
+    fn consume(&mut self, amt: usize) {
+        if amt == 0 {
+            return;
+        }
+        if self.carriage_return {
+            self.carriage_return = false;
+            self.blank = false;
+            return;
+        }
+        if let Ok(buf) = self.inner.fill_buf() {
+            self.blank &= buf[..amt].iter().all(|b| matches!(b, b' ' | b'\t'));
+        }
+        self.skip(amt);
+    }
+}
+
+// validates every line of newline-delimited JSON (NDJSON, JSON Lines) as a
+// document of its own, yielding one result per line that is not blank; the
+// input must be UTF-8, with a byte order mark only at its very start
+pub struct NdjsonLines<R, V = ()> {
+    reader: R,
+    options: ParserOptions,
+    visitor: V,
+    position: Position,
+    // whether the line before `position` was invalid and is not all read yet
+    unread: bool,
+    done: bool,
+}
+
+impl<R: BufRead> NdjsonLines<R> {
+    // calls `visitor` back on every line, like `visit` does on a document
+    pub fn with_visitor<V: JsonVisitor>(self, visitor: V) -> NdjsonLines<R, V> {
+        NdjsonLines {
+            reader: self.reader,
+            options: self.options,
+            visitor,
+            position: self.position,
+            unread: self.unread,
+            done: self.done,
+        }
+    }
+}
+
+impl<R: BufRead, V: JsonVisitor> NdjsonLines<R, V> {
+    pub fn get_ref(&self) -> &R {
+        &self.reader
+    }
+
+    // how many bytes the input starts with that are not part of its first line,
+    // or the error when there are no lines to read; `None` at the end of the input
+    fn start(&mut self) -> Option<Result<usize, JSONError>> {
+        let span = Span::at(self.position);
+        let buf = match self.reader.fill_buf() {
+            Ok(buf) => buf,
+            Err(err) => return Some(Err(JSONError::new(JSONErrorKind::Io(err.into()), span))),
+        };
+        if buf.is_empty() {
+            return None;
+        }
+        if self.position.offset > 0 {
+            return Some(Ok(0));
+        }
+        match Encoding::detect(buf) {
+            (Encoding::Utf8, bom) => {
+                self.reader.consume(bom);
+                Some(Ok(bom))
+            }
+            // lines cannot even be told apart in other encodings
+            (encoding, _) => {
+                let kind = JSONErrorKind::UnsupportedEncoding(encoding);
+                Some(Err(JSONError::new(kind, span)))
+            }
+        }
+    }
+}
+
+fn check_line<R: BufRead, V: JsonVisitor>(
+    line: &mut Line<'_, R>,
+    options: &ParserOptions,
+    visitor: &mut V,
+) -> Result<(), JSONError> {
+    let start = line.start;
+    let tokens = Lexer::with_options(line, options)
+        .starting_at(start)
+        .with_encoding(Encoding::Utf8);
+    parse_with(tokens, options, visitor)
+}
+
+impl<R: BufRead, V: JsonVisitor> Iterator for NdjsonLines<R, V> {
+    type Item = Result<(), JSONError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.unread {
+            self.unread = false;
+            let mut line = Line::new(&mut self.reader, self.position, &self.options);
+            if let Err(err) = line.drain() {
+                self.done = true;
+                return Some(Err(err));
+            }
+            self.position.offset += line.read;
+        }
+        while !self.done {
+            let mut start = self.position;
+            match self.start() {
+                Some(Ok(bom)) => start.offset += bom,
+                Some(Err(err)) => {
+                    self.done = true;
+                    return Some(Err(err));
+                }
+                None => {
+                    self.done = true;
+                    break;
+                }
+            }
+
+            let mut line = Line::new(&mut self.reader, start, &self.options);
+            let mut result = check_line(&mut line, &self.options, &mut self.visitor);
+            match result.as_ref().map_err(JSONError::kind) {
+                Err(JSONErrorKind::SizeLimitExceeded(_) | JSONErrorKind::Io(_)) => {
+                    self.done = true;
+                    return Some(result);
+                }
+                // the line is not blank, so the error is reported right away and
+                // the rest of the line only read when the next one is asked for
+                Err(_) if !line.blank => self.unread = !line.done,
+                Err(_) => {
+                    if let Err(err) = line.drain() {
+                        self.done = true;
+                        result = Err(err);
+                    }
+                }
+                Ok(()) => {}
+            }
+            let (read, blank) = (line.read, line.blank);
+            self.position = Position::new(start.offset + read, start.line + 1, 1);
+            if !blank {
+                return Some(result);
+            }
+        }
+        None
+    }
+}
+
+pub fn check_ndjson<R: BufRead>(reader: R, options: &ParserOptions) -> NdjsonLines<R> {
+    NdjsonLines {
+        reader,
+        options: options.clone(),
+        visitor: (),
+        position: Position::start(),
+        unread: false,
+        done: false,
+    }
+}
+
+#[cfg(test)]
+mod ndjson_tests {
+    use std::io::BufReader;
+
+    use super::*;
+    use crate::parser::{
+        options::{AllowedRoots, DuplicateKeys},
+        test_utils::describe,
+    };
+
+    fn check(input: &str) -> Vec<Result<(), (JSONErrorKind, usize, usize)>> {
+        check_ndjson(input.as_bytes(), &ParserOptions::new())
+            .map(|res| res.map_err(|err| describe(&err)))
+            .collect()
+    }
+
+    #[test]
+    fn should_check_every_line_on_its_own() {
+        assert_eq!(
+            vec![Ok(()), Err((JSONErrorKind::UnexpectedEof, 2, 6)), Ok(()),],
+            check("{\"a\": 1}\n{\"a\":\n[1, 2]\n")
+        );
+    }
+
+    #[test]
+    fn should_report_errors_with_file_positions() {
+        let input = "{}\n{}\n{\"a\": 1}\n{\"a\" 1}";
+        let err = check_ndjson(input.as_bytes(), &ParserOptions::new())
+            .find_map(Result::err)
+            .unwrap();
+        assert_eq!(
+            "Unexpected '<number>', expected ':' after the key: at line 4, column 6",
+            err.to_string()
+        );
+        assert_eq!(20, err.offset());
+    }
+
+    #[test]
+    fn should_skip_blank_lines_and_carriage_returns() {
+        assert_eq!(vec![Ok(()), Ok(())], check("\n{}\r\n  \n[]\r\n\n"));
+    }
+
+    #[test]
+    fn should_not_accept_documents_spanning_lines() {
+        assert_eq!(
+            vec![
+                Err((JSONErrorKind::UnexpectedEof, 1, 2)),
+                Err((
+                    JSONErrorKind::UnexpectedToken {
+                        found: crate::parser::Token::ClosedBrace,
+                        expected: Some("'{' or '['")
+                    },
+                    2,
+                    1
+                )),
+            ],
+            check("{\n}")
+        );
+    }
+
+    #[test]
+    fn should_only_accept_utf8() {
+        assert_eq!(vec![Ok(()), Ok(())], check("\u{FEFF}{}\n[]\n"));
+        assert_eq!(
+            vec![
+                Ok(()),
+                Err((JSONErrorKind::UnexpectedChar('\u{FEFF}'), 2, 1))
+            ],
+            check("{\"a\":1}\n\u{FEFF}{\"b\":2}")
+        );
+        let utf16: Vec<u8> = "{}\n[]\n".bytes().flat_map(|b| [b, 0]).collect();
+        let results: Vec<_> = check_ndjson(&utf16[..], &ParserOptions::new())
+            .map(|res| res.map_err(|err| describe(&err)))
+            .collect();
+        assert_eq!(
+            vec![Err((
+                JSONErrorKind::UnsupportedEncoding(Encoding::Utf16Le),
+                1,
+                1
+            ))],
+            results
+        );
+    }
+
+    #[test]
+    fn should_check_lines_as_they_are_read() {
+        let endless_line = || BufReader::new("[\"".as_bytes().chain(io::repeat(b'a')));
+        let options = ParserOptions::new().max_size(1000);
+        let results: Vec<_> = check_ndjson(endless_line(), &options)
+            .map(|res| res.map_err(|err| describe(&err)))
+            .collect();
+        assert_eq!(
+            vec![Err((JSONErrorKind::SizeLimitExceeded(1000), 1, 1001))],
+            results
+        );
+        let options = ParserOptions::new().max_string_length(100);
+        let first = check_ndjson(endless_line(), &options).next().unwrap();
+        assert_eq!(
+            Err((JSONErrorKind::StringLengthLimitExceeded(100), 1, 2)),
+            first.map_err(|err| describe(&err))
+        );
+    }
+
+    #[test]
+    fn should_find_line_breaks_across_buffers() {
+        let input = "{}\r\n\t\r\n[1, \r\n  \n1]";
+        let results: Vec<_> = check_ndjson(
+            BufReader::with_capacity(3, input.as_bytes()),
+            &ParserOptions::new(),
+        )
+        .map(|res| res.map_err(|err| describe(&err)))
+        .collect();
+        assert_eq!(
+            vec![
+                Ok(()),
+                Err((JSONErrorKind::UnexpectedEof, 3, 4)),
+                Err((
+                    JSONErrorKind::UnexpectedToken {
+                        found: crate::parser::Token::Number(1.0.into()),
+                        expected: Some("'{' or '['")
+                    },
+                    5,
+                    1
+                ))
+            ],
+            results
+        );
+    }
+
+    #[test]
+    fn should_call_the_visitor_back_on_every_line() {
+        struct Duplicates(Vec<(String, usize, usize)>);
+
+        impl JsonVisitor for Duplicates {
+            fn duplicate_key(&mut self, key: &str, span: Span) {
+                self.0
+                    .push((key.to_string(), span.start.line, span.start.column));
+            }
+        }
+
+        let options = ParserOptions::new().duplicate_keys(DuplicateKeys::Warn);
+        let input = "{\"a\": 1, \"a\": 2}\n\n[{\"b\": 1, \"b\": 2}]\n";
+        let mut lines = check_ndjson(input.as_bytes(), &options).with_visitor(Duplicates(vec![]));
+        assert_eq!(Some(Ok(())), lines.next());
+        assert_eq!(Some(Ok(())), lines.next());
+        assert_eq!(None, lines.next());
+        assert_eq!(
+            vec![("a".to_string(), 1, 10), ("b".to_string(), 3, 11)],
+            lines.visitor.0
+        );
+    }
+
+    #[test]
+    fn should_follow_parser_options() {
+        let options = ParserOptions::new().allowed_roots(AllowedRoots::Any);
+        let results: Vec<_> = check_ndjson("1\n\"a\"\nnull\n".as_bytes(), &options).collect();
+        assert_eq!(vec![Ok(()), Ok(()), Ok(())], results);
+    }
+}
@@ -0,0 +1,395 @@
+use core::fmt;
+
+#[cfg(feature = "arbitrary_precision")]
+use core::cmp::Ordering;
+
+#[cfg(feature = "arbitrary_precision")]
+use bigdecimal::BigDecimal;
+#[cfg(feature = "arbitrary_precision")]
+use num_bigint::BigInt;
+
+// a number as it is written in the document, only converted when asked for, so
+// that integers beyond 2^53 and decimals with more digits than a f64 keeps come
+// out of a parse unchanged
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct JsonNumber {
+    // in the RFC 8259 grammar, or one of "Infinity", "-Infinity" and "NaN"
+    lexeme: String,
+}
+
+impl JsonNumber {
+    // `lexeme` is a number the lexer accepted: JSON5 can write some in ways
+    // JSON cannot, those are rewritten without changing their value
+    pub(crate) fn from_lexeme(lexeme: &str) -> JsonNumber {
+        let (sign, digits) = match lexeme.strip_prefix('-') {
+            Some(digits) => ("-", digits),
+            None => ("", lexeme),
+        };
+        let digits = match digits.strip_prefix("0x").or(digits.strip_prefix("0X")) {
+            Some(hex) => hex_to_decimal(hex),
+            None => without_bare_point(digits),
+        };
+        JsonNumber {
+            lexeme: format!("{}{}", sign, digits),
+        }
+    }
+
+    // exactly as written, apart from the JSON5 forms
+    pub fn as_str(&self) -> &str {
+        &self.lexeme
+    }
+
+    pub fn is_finite(&self) -> bool {
+        !matches!(self.lexeme.as_str(), "Infinity" | "-Infinity" | "NaN")
+    }
+
+    // the closest f64, which is infinite for numbers out of its range
+    pub fn as_f64(&self) -> f64 {
+        self.lexeme.parse().expect("a number the lexer accepted")
+    }
+
+    // only for integers written without a fraction or an exponent
+    pub fn as_i64(&self) -> Option<i64> {
+        self.lexeme.parse().ok()
+    }
+
+    pub fn as_u64(&self) -> Option<u64> {
+        self.lexeme.parse().ok()
+    }
+}
+
+#[cfg(feature = "arbitrary_precision")]
+impl JsonNumber {
+    // like `as_i64`, however many digits there are
+    pub fn as_big_int(&self) -> Option<BigInt> {
+        self.lexeme.parse().ok()
+    }
+
+    // exact for every finite number whose exponent fits in an i64, `None` for
+    // the others
+    pub fn as_big_decimal(&self) -> Option<BigDecimal> {
+        if !self.is_finite() {
+            return None;
+        }
+        self.lexeme.parse().ok()
+    }
+
+    // compares values rather than lexemes, so that "1.0" equals "1" and numbers
+    // a f64 cannot tell apart are still ordered; `None` only when one is NaN
+    pub fn compare(&self, other: &JsonNumber) -> Option<Ordering> {
+        if !self.is_finite() || !other.is_finite() {
+            // against infinities and NaN every finite number compares like 0
+            let value = |n: &JsonNumber| if n.is_finite() { 0. } else { n.as_f64() };
+            return value(self).partial_cmp(&value(other));
+        }
+        // no BigDecimal is built, which with an exponent like 1e1000000000
+        // would take billions of digits to compare
+        let (a, b) = (Scientific::of(&self.lexeme), Scientific::of(&other.lexeme));
+        let magnitudes = a
+            .exponent
+            .cmp(&b.exponent)
+            .then_with(|| a.digits.cmp(&b.digits));
+        Some(a.sign.cmp(&b.sign).then(match a.sign {
+            Ordering::Less => magnitudes.reverse(),
+            Ordering::Equal => Ordering::Equal,
+            Ordering::Greater => magnitudes,
+        }))
+    }
+}
+
+// a finite number written 0.d1d2... × 10^exponent, with no leading or trailing
+// zero among the digits; 0 has none, and the sign `Equal`
+#[cfg(feature = "arbitrary_precision")]
+struct Scientific {
+    sign: Ordering,
+    digits: String,
+    exponent: BigInt,
+}
+
+#[cfg(feature = "arbitrary_precision")]
+impl Scientific {
+    fn of(lexeme: &str) -> Scientific {
+        let (sign, unsigned) = match lexeme.strip_prefix('-') {
+            Some(unsigned) => (Ordering::Less, unsigned),
+            None => (Ordering::Greater, lexeme.trim_start_matches('+')),
+        };
+        let (mantissa, exponent) = unsigned.split_once(['e', 'E']).unwrap_or((unsigned, "0"));
+        let exponent: BigInt = exponent
+            .trim_start_matches('+')
+            .parse()
+            .expect("an exponent the lexer accepted");
+        let (integer, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+        let all_digits = format!("{}{}", integer, fraction);
+        let digits = all_digits.trim_start_matches('0');
+        let leading_zeros = all_digits.len() - digits.len();
+        let digits = digits.trim_end_matches('0');
+        if digits.is_empty() {
+            return Scientific {
+                sign: Ordering::Equal,
+                digits: String::new(),
+                exponent: BigInt::from(0),
+            };
+        }
+        Scientific {
+            sign,
+            digits: digits.to_string(),
+            exponent: exponent + integer.len() as i64 - leading_zeros as i64,
+        }
+    }
+}
+
+// JSON5 accepts "5." and ".5", which JSON writes "5" and "0.5"
+fn without_bare_point(digits: &str) -> String {
+    let mut digits = digits.to_string();
+    if let Some(point) = digits.find('.') {
+        if !digits[point + 1..].starts_with(|c: char| c.is_ascii_digit()) {
+            digits.remove(point);
+        }
+        if point == 0 {
+            digits.insert(0, '0');
+        }
+    }
+    digits
+}
+
+// exact, however many digits there are
+fn hex_to_decimal(hex: &str) -> String {
+    // least significant first
+    let mut decimal = vec![0];
+    for digit in hex.chars().filter_map(|d| d.to_digit(16)) {
+        let mut carry = digit;
+        for d in decimal.iter_mut() {
+            let n = *d * 16 + carry;
+            *d = n % 10;
+            carry = n / 10;
+        }
+        while carry > 0 {
+            decimal.push(carry % 10);
+            carry /= 10;
+        }
+    }
+    decimal
+        .iter()
+        .rev()
+        .map(|d| char::from_digit(*d, 10).expect("a decimal digit"))
+        .collect()
+}
+
+// the shortest lexeme that reads back as the same f64
+impl From<f64> for JsonNumber {
+    fn from(n: f64) -> JsonNumber {
+        let lexeme = if n.is_nan() {
+            "NaN".to_string()
+        } else if n.is_infinite() {
+            (if n > 0. { "Infinity" } else { "-Infinity" }).to_string()
+        } else if n.fract() == 0. && n.abs() < 1e17 {
+            format!("{}", n)
+        } else {
+            format!("{:?}", n)
+        };
+        JsonNumber { lexeme }
+    }
+}
+
+impl From<i64> for JsonNumber {
+    fn from(n: i64) -> JsonNumber {
+        JsonNumber {
+            lexeme: n.to_string(),
+        }
+    }
+}
+
+impl From<u64> for JsonNumber {
+    fn from(n: u64) -> JsonNumber {
+        JsonNumber {
+            lexeme: n.to_string(),
+        }
+    }
+}
+
+#[cfg(feature = "arbitrary_precision")]
+impl From<BigInt> for JsonNumber {
+    fn from(n: BigInt) -> JsonNumber {
+        JsonNumber {
+            lexeme: n.to_string(),
+        }
+    }
+}
+
+#[cfg(feature = "arbitrary_precision")]
+impl From<BigDecimal> for JsonNumber {
+    fn from(n: BigDecimal) -> JsonNumber {
+        JsonNumber {
+            lexeme: n.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for JsonNumber {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.lexeme)
+    }
+}
+
+#[cfg(test)]
+mod number_tests {
+    use super::JsonNumber;
+
+    #[test]
+    fn should_rewrite_json5_forms() {
+        for (json5, json) in [
+            ("0x1F", "31"),
+            ("-0XfF", "-255"),
+            ("0xFFFFFFFFFFFFFFFFFFFF", "1208925819614629174706175"),
+            (".5", "0.5"),
+            ("-.5e1", "-0.5e1"),
+            ("5.", "5"),
+            ("5.e1", "5e1"),
+            ("1.50", "1.50"),
+            ("-0", "-0"),
+        ] {
+            assert_eq!(json, JsonNumber::from_lexeme(json5).as_str());
+        }
+    }
+
+    #[test]
+    fn should_convert_on_demand() {
+        let big = JsonNumber::from_lexeme("12345678901234567890");
+        assert_eq!(None, big.as_i64());
+        assert_eq!(Some(12345678901234567890), big.as_u64());
+        assert_eq!(1.2345678901234567e19, big.as_f64());
+        let negative = JsonNumber::from_lexeme("-9223372036854775808");
+        assert_eq!(Some(i64::MIN), negative.as_i64());
+        assert_eq!(None, negative.as_u64());
+        assert_eq!(None, JsonNumber::from_lexeme("1e2").as_i64());
+        assert_eq!(None, JsonNumber::from_lexeme("1.0").as_u64());
+        assert_eq!(f64::INFINITY, JsonNumber::from_lexeme("1e400").as_f64());
+        assert!(JsonNumber::from(f64::NAN).as_f64().is_nan());
+        assert!(!JsonNumber::from(f64::NEG_INFINITY).is_finite());
+    }
+
+    #[test]
+    fn should_write_shortest_lexeme_of_f64() {
+        assert_eq!("42", JsonNumber::from(42.).as_str());
+        assert_eq!("-0", JsonNumber::from(-0.).as_str());
+        assert_eq!("0.1", JsonNumber::from(0.1).as_str());
+        assert_eq!("1e17", JsonNumber::from(1e17).as_str());
+        assert_eq!("-Infinity", JsonNumber::from(f64::NEG_INFINITY).as_str());
+        assert_eq!("18446744073709551615", JsonNumber::from(u64::MAX).as_str());
+    }
+}
+
+#[cfg(all(test, feature = "arbitrary_precision"))]
+mod arbitrary_precision_tests {
+    use core::cmp::Ordering;
+    use std::str::FromStr;
+
+    use bigdecimal::BigDecimal;
+    use num_bigint::BigInt;
+
+    use super::JsonNumber;
+    use crate::parser::{parse_value, JsonValue};
+    use crate::serializer::to_string;
+
+    fn number(lexeme: &str) -> JsonNumber {
+        JsonNumber::from_lexeme(lexeme)
+    }
+
+    #[test]
+    fn should_read_big_integers_and_decimals() {
+        let digits = "123456789012345678901234567890";
+        assert_eq!(
+            Some(BigInt::from_str(digits).unwrap()),
+            number(digits).as_big_int()
+        );
+        assert_eq!(None, number("1.5").as_big_int());
+        assert_eq!(None, number("1e3").as_big_int());
+        let amount = "-1234567890123456789012.345678901234";
+        assert_eq!(
+            Some(BigDecimal::from_str(amount).unwrap()),
+            number(amount).as_big_decimal()
+        );
+        assert_eq!(
+            Some(BigDecimal::from_str("120").unwrap()),
+            number("1.2E+2").as_big_decimal()
+        );
+        assert_eq!(None, JsonNumber::from(f64::INFINITY).as_big_decimal());
+    }
+
+    #[test]
+    fn should_not_read_exponents_beyond_big_decimals() {
+        let value = parse_value("[1e99999999999999999999]".as_bytes()).unwrap();
+        let huge = value
+            .as_array()
+            .and_then(|values| values[0].as_number())
+            .unwrap();
+        assert_eq!(None, huge.as_big_decimal());
+        assert_eq!(f64::INFINITY, huge.as_f64());
+    }
+
+    #[test]
+    fn should_compare_values_exactly() {
+        let compare = |a: &str, b: &str| number(a).compare(&number(b));
+        assert_eq!(Some(Ordering::Equal), compare("1.0", "1"));
+        assert_eq!(Some(Ordering::Equal), compare("1e2", "100.00"));
+        assert_eq!(Some(Ordering::Equal), compare("-0", "0"));
+        assert_eq!(
+            Some(Ordering::Less),
+            compare(
+                "0.1000000000000000000000000000001",
+                "0.1000000000000000000000000000002"
+            )
+        );
+        assert_eq!(
+            Some(Ordering::Greater),
+            compare(
+                "123456789012345678901234567891",
+                "123456789012345678901234567890"
+            )
+        );
+        assert_eq!(Some(Ordering::Less), compare("1e400", "Infinity"));
+        assert_eq!(Some(Ordering::Greater), compare("-1e400", "-Infinity"));
+        assert_eq!(None, compare("NaN", "1"));
+    }
+
+    #[test]
+    fn should_compare_extreme_exponents_by_magnitude() {
+        let compare = |a: &str, b: &str| number(a).compare(&number(b));
+        assert_eq!(Some(Ordering::Greater), compare("1e1000000000", "1.5"));
+        assert_eq!(Some(Ordering::Less), compare("-1e1000000000", "1.5"));
+        assert_eq!(Some(Ordering::Less), compare("-1e1000000000", "-1.5"));
+        assert_eq!(Some(Ordering::Greater), compare("1e-1000000000", "0"));
+        assert_eq!(
+            Some(Ordering::Less),
+            compare("1e-1000000000", "1e-999999999")
+        );
+        assert_eq!(
+            Some(Ordering::Equal),
+            compare("1e99999999999999999999", "0.010e+100000000000000000001")
+        );
+        assert_eq!(
+            Some(Ordering::Less),
+            compare("1.2e99999999999999999999", "1.25e99999999999999999999")
+        );
+        assert_eq!(Some(Ordering::Equal), compare("0.000e5", "-0.0"));
+        assert_eq!(Some(Ordering::Equal), compare("120", "1.2E+2"));
+        assert_eq!(
+            Some(Ordering::Less),
+            compare("1e99999999999999999999", "Infinity")
+        );
+    }
+
+    #[test]
+    fn should_write_big_numbers_back_exactly() {
+        let input = "{\"amount\":12345678901234567890.123456789012345678901234567890}";
+        let value = parse_value(input.as_bytes()).unwrap();
+        assert_eq!(input, to_string(&value));
+        let amount = value.get("amount").and_then(JsonValue::as_number).unwrap();
+        let doubled = amount.as_big_decimal().unwrap() * BigDecimal::from(2);
+        assert_eq!(
+            "24691357802469135780.246913578024691357802469135780",
+            JsonNumber::from(doubled).as_str()
+        );
+        assert_eq!("-10", JsonNumber::from(BigInt::from(-10)).as_str());
+    }
+}
@@ -0,0 +1,156 @@
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Dialect {
+    // the grammar this parser has always accepted: the root must be an object
+    // or an array, numbers may start with '+' and only ' ' and '\n' are whitespace
+    #[default]
+    Classic,
+    // exactly RFC 8259
+    Strict,
+    // JSON5, a superset of JSON for configuration files: comments, trailing
+    // commas, single-quoted strings, unquoted keys, hexadecimal numbers,
+    // Infinity and NaN
+    Json5,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AllowedRoots {
+    Any,
+    ObjectOrArray,
+    Object,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DuplicateKeys {
+    #[default]
+    Allow,
+    Error,
+    // tells the visitor about every repeated key, see `JsonVisitor::duplicate_key`
+    Warn,
+    // like `Warn`, and a built `JsonValue` keeps the first value of the key
+    FirstWins,
+    // like `Warn`, and a built `JsonValue` keeps the last value of the key, in
+    // place of the first
+    LastWins,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AllowedEncodings {
+    // UTF-8, UTF-16 or UTF-32, detected from the start of the input
+    Any,
+    Utf8,
+}
+
+// what to do with input that does not decode, in UTF-8 or in the detected encoding
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InvalidUtf8 {
+    #[default]
+    Error,
+    // read every invalid sequence as U+FFFD, the replacement character
+    Replace,
+}
+
+// how deep the functions building a `JsonValue`, like `parse_value`, let a
+// document nest unless `max_depth` says otherwise: dropping, cloning, comparing
+// and printing a `JsonValue` recurse once per level and would overflow the
+// stack on much deeper values
+pub const DEFAULT_VALUE_DEPTH: usize = 128;
+
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ParserOptions {
+    pub(crate) dialect: Dialect,
+    // when not set, the dialect decides
+    pub(crate) allowed_roots: Option<AllowedRoots>,
+    pub(crate) duplicate_keys: DuplicateKeys,
+    pub(crate) max_depth: Option<usize>,
+    pub(crate) max_string_length: Option<usize>,
+    pub(crate) max_number_length: Option<usize>,
+    pub(crate) max_size: Option<usize>,
+    pub(crate) max_members: Option<usize>,
+    pub(crate) invalid_utf8: InvalidUtf8,
+    // when not set, the dialect decides
+    pub(crate) allowed_encodings: Option<AllowedEncodings>,
+}
+
+impl ParserOptions {
+    pub fn new() -> ParserOptions {
+        ParserOptions::default()
+    }
+
+    pub fn dialect(mut self, dialect: Dialect) -> ParserOptions {
+        self.dialect = dialect;
+        self
+    }
+
+    pub fn allowed_roots(mut self, allowed_roots: AllowedRoots) -> ParserOptions {
+        self.allowed_roots = Some(allowed_roots);
+        self
+    }
+
+    pub fn duplicate_keys(mut self, duplicate_keys: DuplicateKeys) -> ParserOptions {
+        self.duplicate_keys = duplicate_keys;
+        self
+    }
+
+    // how many objects and arrays can be open at the same time
+    pub fn max_depth(mut self, max_depth: usize) -> ParserOptions {
+        self.max_depth = Some(max_depth);
+        self
+    }
+
+    // in bytes once unescaped, for keys too
+    pub fn max_string_length(mut self, max_string_length: usize) -> ParserOptions {
+        self.max_string_length = Some(max_string_length);
+        self
+    }
+
+    // in characters, sign and exponent included
+    pub fn max_number_length(mut self, max_number_length: usize) -> ParserOptions {
+        self.max_number_length = Some(max_number_length);
+        self
+    }
+
+    // how many bytes of input can be read
+    pub fn max_size(mut self, max_size: usize) -> ParserOptions {
+        self.max_size = Some(max_size);
+        self
+    }
+
+    // how many members an object, or elements an array, can have
+    pub fn max_members(mut self, max_members: usize) -> ParserOptions {
+        self.max_members = Some(max_members);
+        self
+    }
+
+    pub fn invalid_utf8(mut self, invalid_utf8: InvalidUtf8) -> ParserOptions {
+        self.invalid_utf8 = invalid_utf8;
+        self
+    }
+
+    pub fn allowed_encodings(mut self, allowed_encodings: AllowedEncodings) -> ParserOptions {
+        self.allowed_encodings = Some(allowed_encodings);
+        self
+    }
+
+    // the options to build a `JsonValue` with
+    pub(crate) fn for_values(&self) -> ParserOptions {
+        ParserOptions {
+            max_depth: Some(self.max_depth.unwrap_or(DEFAULT_VALUE_DEPTH)),
+            ..self.clone()
+        }
+    }
+
+    pub(crate) fn roots(&self) -> AllowedRoots {
+        self.allowed_roots.unwrap_or(match self.dialect {
+            Dialect::Classic => AllowedRoots::ObjectOrArray,
+            Dialect::Strict | Dialect::Json5 => AllowedRoots::Any,
+        })
+    }
+
+    // RFC 8259 requires UTF-8
+    pub(crate) fn encodings(&self) -> AllowedEncodings {
+        self.allowed_encodings.unwrap_or(match self.dialect {
+            Dialect::Strict => AllowedEncodings::Utf8,
+            Dialect::Classic | Dialect::Json5 => AllowedEncodings::Any,
+        })
+    }
+}
@@ -1,184 +1,342 @@
-use super::{error::JSONError, token::Token};
+use std::collections::HashSet;
+
+use super::{
+    error::{JSONError, JSONErrorKind},
+    options::{AllowedRoots, Dialect, DuplicateKeys, ParserOptions},
+    token::{Position, Span, SpannedToken, Token},
+    events::JsonVisitor,
+    value::JsonValue,
+};
 
 #[derive(Debug)]
-struct State {
+pub struct State {
     state_kind: StateKind,
     obj_arr_stack: Vec<ObjArr>,
+    options: ParserOptions,
 }
 
 impl State {
-    pub fn new() -> State {
+    pub fn new(options: &ParserOptions) -> State {
         State {
             state_kind: StateKind::Initial,
             obj_arr_stack: vec![],
+            options: options.clone(),
+        }
+    }
+
+    fn expects_value(&self, token: &Token) -> bool {
+        match self.state_kind {
+            StateKind::ObjVal | StateKind::OpenArr | StateKind::ArrValAfterComma => true,
+            StateKind::Initial => matches!(
+                (self.options.roots(), token),
+                (AllowedRoots::Any, _)
+                    | (AllowedRoots::ObjectOrArray, Token::OpenBrace | Token::OpenBracket)
+                    | (AllowedRoots::Object, Token::OpenBrace)
+            ),
+            _ => false,
         }
     }
 
-    fn close_obj(&mut self) -> Result<(), JSONError> {
-        self.state_kind = match self.obj_arr_stack.pop() {
-            Some(ObjArr::RootObj) => StateKind::End,
-            Some(ObjArr::Object) => StateKind::AfterObjVal,
-            Some(_) | None => return Err(JSONError::new("Unexpected '}'".to_string(), 1)),
+    fn after_value(&mut self) {
+        self.state_kind = match self.obj_arr_stack.last() {
+            Some(ObjArr::Object(..)) => StateKind::AfterObjVal,
+            Some(ObjArr::Array(_)) => StateKind::ArrVal,
+            None => StateKind::End,
         };
+    }
+
+    fn value<V: JsonVisitor>(
+        &mut self,
+        visitor: &mut V,
+        value: JsonValue,
+        span: Span,
+    ) -> Result<(), JSONError> {
+        self.add_member(span)?;
+        visitor.value(value);
+        self.after_value();
         Ok(())
     }
 
-    fn open_obj(&mut self) {
-        self.state_kind = StateKind::OpenObj;
-        self.obj_arr_stack
-            .push(if let Some(_) = self.obj_arr_stack.last() {
-                ObjArr::Object
-            } else {
-                ObjArr::RootObj
-            })
+    // counts a value against the object or array it is in
+    fn add_member(&mut self, span: Span) -> Result<(), JSONError> {
+        let members = match self.obj_arr_stack.last_mut() {
+            Some(ObjArr::Object(_, members) | ObjArr::Array(members)) => members,
+            None => return Ok(()),
+        };
+        *members += 1;
+        match self.options.max_members {
+            Some(limit) if *members > limit => Err(JSONError::new(
+                JSONErrorKind::MemberLimitExceeded(limit),
+                span,
+            )),
+            _ => Ok(()),
+        }
     }
 
-    fn open_arr(&mut self) {
-        self.state_kind = StateKind::OpenArr;
-        self.obj_arr_stack
-            .push(if let Some(_) = self.obj_arr_stack.last() {
-                ObjArr::Array
-            } else {
-                ObjArr::RootArr
-            })
+    fn unexpected(&self, found: Token, span: Span) -> JSONError {
+        JSONError::new(
+            JSONErrorKind::UnexpectedToken {
+                found,
+                expected: Some(match (&self.state_kind, self.options.roots()) {
+                    (StateKind::Initial, AllowedRoots::Any) => "a value",
+                    (StateKind::Initial, AllowedRoots::Object) => "'{'",
+                    (state_kind, _) => state_kind.expected(),
+                }),
+            },
+            span,
+        )
     }
 
-    fn close_arr(&mut self) -> Result<(), JSONError> {
-        self.state_kind = match self.obj_arr_stack.pop() {
-            Some(ObjArr::RootArr) => StateKind::End,
-            Some(ObjArr::Array) => StateKind::ArrVal,
-            Some(_) | None => return Err(JSONError::new("Unexpected ']'".to_string(), 1)),
+    fn close_obj(&mut self, span: Span) -> Result<(), JSONError> {
+        match self.obj_arr_stack.pop() {
+            Some(ObjArr::Object(..)) => {}
+            Some(_) | None => return Err(self.unexpected(Token::ClosedBrace, span)),
         };
+        self.after_value();
         Ok(())
     }
-}
 
-#[derive(Debug, PartialEq)]
-enum ObjArr {
-    RootArr,
-    RootObj,
-    Object,
-    Array,
-}
+    fn check_depth(&self, span: Span) -> Result<(), JSONError> {
+        match self.options.max_depth {
+            Some(limit) if self.obj_arr_stack.len() >= limit => Err(JSONError::new(
+                JSONErrorKind::DepthLimitExceeded(limit),
+                span,
+            )),
+            _ => Ok(()),
+        }
+    }
 
-#[derive(Debug, PartialEq)]
-enum StateKind {
-    Initial,
+    fn open_obj(&mut self, span: Span) -> Result<(), JSONError> {
+        self.check_depth(span)?;
+        self.add_member(span)?;
+        self.state_kind = StateKind::OpenObj;
+        self.obj_arr_stack.push(ObjArr::Object(HashSet::new(), 0));
+        Ok(())
+    }
 
-    OpenObj,
-    OpenArr,
+    fn open_arr(&mut self, span: Span) -> Result<(), JSONError> {
+        self.check_depth(span)?;
+        self.add_member(span)?;
+        self.state_kind = StateKind::OpenArr;
+        self.obj_arr_stack.push(ObjArr::Array(0));
+        Ok(())
+    }
 
-    ObjKey,
-    ObjVal,
-    AfterObjVal,
-    ObjComma,
-    
-    ArrVal,
-    ArrValAfterComma,
+    fn key<V: JsonVisitor>(
+        &mut self,
+        visitor: &mut V,
+        key: String,
+        span: Span,
+    ) -> Result<(), JSONError> {
+        let policy = self.options.duplicate_keys;
+        if policy != DuplicateKeys::Allow {
+            if let Some(ObjArr::Object(keys, _)) = self.obj_arr_stack.last_mut() {
+                if keys.contains(&key) {
+                    if policy == DuplicateKeys::Error {
+                        return Err(JSONError::new(JSONErrorKind::DuplicateKey(key), span));
+                    }
+                    visitor.duplicate_key(&key, span);
+                } else {
+                    keys.insert(key.clone());
+                }
+            }
+        }
+        self.state_kind = StateKind::ObjKey;
+        visitor.key(key);
+        Ok(())
+    }
 
-    End,
-}
+    // the spelling of `true`, `null`, `Infinity` and the other words the lexer
+    // reads as values, when one is written where JSON5 expects a key
+    pub fn keyword_key(&self, token: &Token, span: Span) -> Option<String> {
+        let at_key = matches!(self.state_kind, StateKind::OpenObj | StateKind::ObjComma);
+        if self.options.dialect != Dialect::Json5 || !at_key {
+            return None;
+        }
+        let key = match token {
+            Token::BoolTrue => "true",
+            Token::BoolFalse => "false",
+            Token::Null => "null",
+            Token::Number(n) if matches!(n.as_str(), "Infinity" | "NaN") => n.as_str(),
+            _ => return None,
+        };
+        // "+Infinity" is the same number but not an identifier
+        (span.end.column - span.start.column == key.len()).then(|| key.to_string())
+    }
+
+    fn close_arr(&mut self, span: Span) -> Result<(), JSONError> {
+        match self.obj_arr_stack.pop() {
+            Some(ObjArr::Array(_)) => {}
+            Some(_) | None => return Err(self.unexpected(Token::ClosedBracket, span)),
+        };
+        self.after_value();
+        Ok(())
+    }
 
-pub fn parse(tokens: Vec<Token>) -> Result<(), JSONError> {
-    let mut state = State::new();
-    for token in &tokens {
-        match (&state.state_kind, token) {
+    pub fn step<V: JsonVisitor>(
+        &mut self,
+        SpannedToken { token, span }: SpannedToken,
+        visitor: &mut V,
+    ) -> Result<(), JSONError> {
+        if let Some(key) = self.keyword_key(&token, span) {
+            return self.key(visitor, key, span);
+        }
+        let expects_value = self.expects_value(&token);
+        let json5 = self.options.dialect == Dialect::Json5;
+        match (&self.state_kind, token) {
             (_, Token::NewLine) => {}
-            (StateKind::Initial, Token::OpenBrace) => {
-                state.open_obj();
-            }
-            (StateKind::Initial, Token::ClosedBrace) => {
-                return Err(JSONError::new("Unexpected '}'".to_string(), 1));
+            (_, Token::OpenBrace) if expects_value => {
+                self.open_obj(span)?;
+                visitor.start_object();
             }
-            (StateKind::Initial, Token::OpenBracket) => {
-                state.open_arr();
+            (_, Token::OpenBracket) if expects_value => {
+                self.open_arr(span)?;
+                visitor.start_array();
             }
 
-            (StateKind::End, token) => {
-                dbg!("state end with token", token);
-                return Err(JSONError::new(format!("Unexpected {}", token), 1));
+            (_, Token::StringLiteral(s)) if expects_value => {
+                self.value(visitor, JsonValue::String(s), span)?
             }
-
-            (StateKind::OpenObj, Token::StringLiteral(_)) => {
-                state.state_kind = StateKind::ObjKey;
+            (_, Token::Number(n)) if expects_value => {
+                self.value(visitor, JsonValue::Number(n), span)?
             }
-            (StateKind::OpenObj, Token::ClosedBrace) => {
-                state.close_obj()?;
+            (_, Token::BoolTrue) if expects_value => {
+                self.value(visitor, JsonValue::Bool(true), span)?
             }
-
-            (StateKind::OpenArr, Token::ClosedBracket) => {
-                state.close_arr()?;
-            }
-            (StateKind::OpenArr, Token::OpenBracket) => {
-                state.open_arr();
-            }
-            (
-                StateKind::OpenArr | StateKind::ArrValAfterComma,
-                Token::StringLiteral(_)
-                | Token::BoolFalse
-                | Token::BoolTrue
-                | Token::Null
-                | Token::Number(_),
-            ) => {
-                state.state_kind = StateKind::ArrVal;
+            (_, Token::BoolFalse) if expects_value => {
+                self.value(visitor, JsonValue::Bool(false), span)?
             }
+            (_, Token::Null) if expects_value => self.value(visitor, JsonValue::Null, span)?,
 
-            (StateKind::ObjKey, Token::Column) => {
-                state.state_kind = StateKind::ObjVal;
+            (StateKind::OpenObj | StateKind::ObjComma, Token::StringLiteral(key)) => {
+                self.key(visitor, key, span)?
             }
-
-            (StateKind::ArrVal, Token::ClosedBracket) => {
-                state.close_arr()?;
+            (StateKind::OpenObj | StateKind::ObjComma, Token::Identifier(key)) if json5 => {
+                self.key(visitor, key, span)?
             }
-            (StateKind::ArrVal, Token::ClosedBrace) => {
-                state.close_obj()?;
+            (StateKind::ObjKey, Token::Column) => {
+                self.state_kind = StateKind::ObjVal;
             }
-            (StateKind::ArrVal, Token::Comma) => state.state_kind = StateKind::ArrValAfterComma,
-
-            (
-                StateKind::ObjVal,
-                Token::StringLiteral(_)
-                | Token::BoolFalse
-                | Token::BoolTrue
-                | Token::Null
-                | Token::Number(_),
-            ) => {
-                state.state_kind = StateKind::AfterObjVal;
+            (StateKind::AfterObjVal, Token::Comma) => {
+                self.state_kind = StateKind::ObjComma;
             }
-            (StateKind::ObjVal, Token::OpenBrace) => {
-                state.open_obj();
+            (StateKind::ObjComma, Token::ClosedBrace) if json5 => {
+                self.close_obj(span)?;
+                visitor.end_object();
             }
-            (StateKind::ObjVal, Token::OpenBracket) => {
-                state.open_arr();
+            (StateKind::OpenObj | StateKind::AfterObjVal, Token::ClosedBrace) => {
+                self.close_obj(span)?;
+                visitor.end_object();
             }
 
-            (StateKind::AfterObjVal, Token::ClosedBrace) => {
-                state.close_obj()?;
+            (StateKind::ArrVal, Token::Comma) => self.state_kind = StateKind::ArrValAfterComma,
+            (StateKind::ArrValAfterComma, Token::ClosedBracket) if json5 => {
+                self.close_arr(span)?;
+                visitor.end_array();
             }
-            (StateKind::AfterObjVal, Token::Comma) => {
-                state.state_kind = StateKind::ObjComma;
-            }
-
-            (StateKind::ObjComma, Token::StringLiteral(_)) => {
-                state.state_kind = StateKind::ObjKey;
+            (StateKind::OpenArr | StateKind::ArrVal, Token::ClosedBracket) => {
+                self.close_arr(span)?;
+                visitor.end_array();
             }
 
             (_, token) => {
-                dbg!("unexpected kind", state, token);
-                return Err(JSONError::new(format!("Unexpected {}", token), 1));
+                return Err(self.unexpected(token, span));
             }
         }
+        Ok(())
+    }
+
+    // whether a whole document has been read
+    pub fn is_done(&self) -> bool {
+        self.state_kind == StateKind::End
+    }
+
+    // `end` is where the input stopped, used to point at a missing token
+    pub fn finish(&self, end: Position) -> Result<(), JSONError> {
+        if self.state_kind != StateKind::End {
+            return Err(JSONError::new(JSONErrorKind::UnexpectedEof, Span::at(end)));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq)]
+enum ObjArr {
+    // the keys seen so far, only tracked when duplicates are rejected, and
+    // the number of members
+    Object(HashSet<String>, usize),
+    Array(usize),
+}
+
+#[derive(Debug, PartialEq)]
+enum StateKind {
+    Initial,
+
+    OpenObj,
+    OpenArr,
+
+    ObjKey,
+    ObjVal,
+    AfterObjVal,
+    ObjComma,
+    
+    ArrVal,
+    ArrValAfterComma,
+
+    End,
+}
+
+impl StateKind {
+    fn expected(&self) -> &'static str {
+        match self {
+            StateKind::Initial => "'{' or '['",
+            StateKind::OpenObj => "a string key or '}'",
+            StateKind::OpenArr => "a value or ']'",
+            StateKind::ObjKey => "':' after the key",
+            StateKind::ObjVal => "a value after ':'",
+            StateKind::AfterObjVal => "',' or '}'",
+            StateKind::ObjComma => "a string key after ','",
+            StateKind::ArrVal => "',' or ']'",
+            StateKind::ArrValAfterComma => "a value after ','",
+            StateKind::End => "the end of the document",
+        }
     }
-    if state.state_kind != StateKind::End {
-        dbg!(state.state_kind);
-        return Err(JSONError::new(format!("Unexpected EOF"), 1));
+}
+
+// pulls the tokens one at a time, so that only the open objects and arrays are
+// held in memory, and stops at the first error whether it comes from the lexer
+// or from the grammar
+pub fn parse<I>(tokens: I, options: &ParserOptions) -> Result<(), JSONError>
+where
+    I: IntoIterator<Item = Result<SpannedToken, JSONError>>,
+{
+    parse_with(tokens, options, &mut ())
+}
+
+pub fn parse_with<I, V>(
+    tokens: I,
+    options: &ParserOptions,
+    visitor: &mut V,
+) -> Result<(), JSONError>
+where
+    I: IntoIterator<Item = Result<SpannedToken, JSONError>>,
+    V: JsonVisitor,
+{
+    let mut state = State::new(options);
+    let mut end = Position::start();
+    for token in tokens {
+        let token = token?;
+        end = token.span.end;
+        state.step(token, visitor)?;
     }
-    Ok(())
+    state.finish(end)
 }
 
 #[cfg(test)]
 mod test_parser_pass {
-    use crate::parser::token::Token;
+    use crate::parser::{
+        number::JsonNumber, options::ParserOptions, test_utils::spanned, token::Token,
+    };
     macro_rules! test_parser_passes {
         ($($name:ident: $value:expr,)*) => {
             use super::parse;
@@ -186,7 +344,7 @@ mod test_parser_pass {
                 #[test]
                 fn $name() {
                     let input = $value;
-                    assert_eq!((), parse(input).unwrap());
+                    assert_eq!((), parse(spanned(input), &ParserOptions::default()).unwrap());
                 }
             )*
             }
@@ -270,7 +428,7 @@ mod test_parser_pass {
             Token::Comma,
             Token::StringLiteral("key5".to_string()),
             Token::Column,
-            Token::Number(101.),
+            Token::Number(JsonNumber::from(101.)),
             Token::NewLine,
             Token::ClosedBrace,
         ],
@@ -294,7 +452,7 @@ mod test_parser_pass {
             Token::Comma,
             Token::StringLiteral("some value".to_string()),
             Token::Comma,
-            Token::Number(1.),
+            Token::Number(JsonNumber::from(1.)),
             Token::ClosedBracket
         ],
         array_with_nested_array: vec![
@@ -318,12 +476,51 @@ mod test_parser_pass {
             Token::ClosedBracket,
             Token::ClosedBrace,
         ],
+        array_with_nested_object: vec![
+            Token::OpenBracket,
+            Token::OpenBrace,
+            Token::ClosedBrace,
+            Token::Comma,
+            Token::OpenBrace,
+            Token::StringLiteral("key".to_string()),
+            Token::Column,
+            Token::Null,
+            Token::ClosedBrace,
+            Token::ClosedBracket
+        ],
+        array_with_value_after_nested_array: vec![
+            Token::OpenBracket,
+            Token::OpenBracket,
+            Token::Number(JsonNumber::from(1.)),
+            Token::ClosedBracket,
+            Token::Comma,
+            Token::Number(JsonNumber::from(2.)),
+            Token::ClosedBracket
+        ],
+        obj_with_value_after_array_value: vec![
+            Token::OpenBrace,
+            Token::StringLiteral("key".to_string()),
+            Token::Column,
+            Token::OpenBracket,
+            Token::ClosedBracket,
+            Token::Comma,
+            Token::StringLiteral("key2".to_string()),
+            Token::Column,
+            Token::Null,
+            Token::ClosedBrace,
+        ],
     }
 }
 
 #[cfg(test)]
 mod test_parser_failure {
-    use crate::parser::{error::JSONError, token::Token};
+    use crate::parser::{
+        error::JSONErrorKind,
+        number::JsonNumber,
+        options::ParserOptions,
+        test_utils::{describe, spanned, unexpected},
+        token::Token,
+    };
     macro_rules! test_parser_fails {
         ($($name:ident: $value:expr,)*) => {
             use super::parse;
@@ -331,7 +528,7 @@ mod test_parser_failure {
                 #[test]
                 fn $name() {
                     let (input, expected_err) = $value;
-                    assert_eq!(expected_err, parse(input).unwrap_err());
+                    assert_eq!(expected_err, describe(&parse(spanned(input), &ParserOptions::default()).unwrap_err()));
                 }
             )*
             }
@@ -340,15 +537,15 @@ mod test_parser_failure {
     test_parser_fails! {
         with_only_open_brace: (
             vec![Token::OpenBrace],
-            JSONError::new("Unexpected EOF".to_string(), 1),
+            (JSONErrorKind::UnexpectedEof, 1, 2),
         ),
         with_no_tokens: (
             vec![],
-            JSONError::new("Unexpected EOF".to_string(), 1),
+            (JSONErrorKind::UnexpectedEof, 1, 1),
         ),
         with_only_closed_brace: (
             vec![Token::ClosedBrace],
-            JSONError::new("Unexpected '}'".to_string(), 1),
+            (unexpected(Token::ClosedBrace, "'{' or '['"), 1, 1),
         ),
         literal_outside_obj: (
             vec![
@@ -359,7 +556,7 @@ mod test_parser_failure {
                 Token::ClosedBrace,
                 Token::StringLiteral("outsider value".to_string()),
             ],
-            JSONError::new("Unexpected '<string literal>'".to_string(), 1),
+            (unexpected(Token::StringLiteral("outsider value".to_string()), "the end of the document"), 1, 6),
         ),
         true_outside_obj: (
             vec![
@@ -367,7 +564,7 @@ mod test_parser_failure {
                 Token::ClosedBrace,
                 Token::BoolTrue,
             ],
-            JSONError::new("Unexpected '<boolean>'".to_string(), 1),
+            (unexpected(Token::BoolTrue, "the end of the document"), 1, 3),
         ),
         false_outside_obj: (
             vec![
@@ -375,7 +572,7 @@ mod test_parser_failure {
                 Token::ClosedBrace,
                 Token::BoolFalse,
             ],
-            JSONError::new("Unexpected '<boolean>'".to_string(), 1),
+            (unexpected(Token::BoolFalse, "the end of the document"), 1, 3),
         ),
         null_outside_obj: (
             vec![
@@ -383,15 +580,15 @@ mod test_parser_failure {
                 Token::ClosedBrace,
                 Token::Null,
             ],
-            JSONError::new("Unexpected '<null>'".to_string(), 1),
+            (unexpected(Token::Null, "the end of the document"), 1, 3),
         ),
         number_outside_obj: (
             vec![
                 Token::OpenBrace,
                 Token::ClosedBrace,
-                Token::Number(0.),
+                Token::Number(JsonNumber::from(0.)),
             ],
-            JSONError::new("Unexpected '<number>'".to_string(), 1),
+            (unexpected(Token::Number(JsonNumber::from(0.)), "the end of the document"), 1, 3),
         ),
         with_closure_after_comma: (
             vec![
@@ -407,7 +604,7 @@ mod test_parser_failure {
                 Token::Comma,
                 Token::ClosedBrace
             ],
-            JSONError::new("Unexpected '}'".to_string(), 1),
+            (unexpected(Token::ClosedBrace, "a string key after ','"), 1, 11),
         ),
         with_extra_comma_in_array: (
             vec![
@@ -416,7 +613,168 @@ mod test_parser_failure {
                 Token::Comma,
                 Token::ClosedBracket,
             ],
-            JSONError::new("Unexpected ']'".to_string(), 1),
+            (unexpected(Token::ClosedBracket, "a value after ','"), 1, 4),
+        ),
+        with_array_closure_in_empty_obj: (
+            vec![Token::OpenBrace, Token::ClosedBracket],
+            (unexpected(Token::ClosedBracket, "a string key or '}'"), 1, 2),
+        ),
+        with_column_in_empty_array: (
+            vec![Token::OpenBracket, Token::Column],
+            (unexpected(Token::Column, "a value or ']'"), 1, 2),
+        ),
+        with_comma_after_key: (
+            vec![
+                Token::OpenBrace,
+                Token::StringLiteral("key".to_string()),
+                Token::Comma,
+            ],
+            (unexpected(Token::Comma, "':' after the key"), 1, 3),
+        ),
+        with_closure_after_column: (
+            vec![
+                Token::OpenBrace,
+                Token::StringLiteral("key".to_string()),
+                Token::Column,
+                Token::ClosedBrace,
+            ],
+            (unexpected(Token::ClosedBrace, "a value after ':'"), 1, 4),
+        ),
+        with_mismatched_array_closure: (
+            vec![
+                Token::OpenBracket,
+                Token::StringLiteral("mismatch".to_string()),
+                Token::ClosedBrace,
+            ],
+            (unexpected(Token::ClosedBrace, "',' or ']'"), 1, 3),
+        ),
+        with_value_after_nested_obj_in_obj: (
+            vec![
+                Token::OpenBrace,
+                Token::StringLiteral("key".to_string()),
+                Token::Column,
+                Token::OpenBrace,
+                Token::ClosedBrace,
+                Token::Null,
+                Token::ClosedBrace,
+            ],
+            (unexpected(Token::Null, "',' or '}'"), 1, 6),
         ),
     }
 }
+
+#[cfg(test)]
+mod test_parser_values {
+    use super::parse_with;
+    use crate::parser::{
+        number::JsonNumber,
+        options::{Dialect, ParserOptions},
+        test_utils::spanned,
+        token::Token,
+        value::{JsonValue, ValueBuilder},
+    };
+
+    fn build(tokens: Vec<Token>) -> JsonValue {
+        let mut builder = ValueBuilder::new();
+        parse_with(spanned(tokens), &ParserOptions::default(), &mut builder).unwrap();
+        builder.finish().unwrap()
+    }
+
+    #[test]
+    fn should_build_scalar_root_in_strict_dialect() {
+        let mut builder = ValueBuilder::new();
+        let strict = ParserOptions::new().dialect(Dialect::Strict);
+        let tokens = spanned(vec![Token::Number(JsonNumber::from(42.))]);
+        parse_with(tokens, &strict, &mut builder).unwrap();
+        assert_eq!(Some(JsonValue::Number(JsonNumber::from(42.))), builder.finish());
+    }
+
+    #[test]
+    fn should_build_empty_object() {
+        assert_eq!(
+            JsonValue::Object(vec![]),
+            build(vec![Token::OpenBrace, Token::ClosedBrace])
+        );
+    }
+
+    #[test]
+    fn should_build_object_members_in_order() {
+        assert_eq!(
+            JsonValue::Object(vec![
+                ("b".to_string(), JsonValue::Number(JsonNumber::from(1.))),
+                ("a".to_string(), JsonValue::String("x".to_string())),
+            ]),
+            build(vec![
+                Token::OpenBrace,
+                Token::StringLiteral("b".to_string()),
+                Token::Column,
+                Token::Number(JsonNumber::from(1.)),
+                Token::Comma,
+                Token::StringLiteral("a".to_string()),
+                Token::Column,
+                Token::StringLiteral("x".to_string()),
+                Token::ClosedBrace,
+            ])
+        );
+    }
+
+    #[test]
+    fn should_build_array_with_all_possible_values() {
+        assert_eq!(
+            JsonValue::Array(vec![
+                JsonValue::Null,
+                JsonValue::Bool(true),
+                JsonValue::Bool(false),
+                JsonValue::String("s".to_string()),
+                JsonValue::Number(JsonNumber::from(1.5)),
+                JsonValue::Array(vec![]),
+                JsonValue::Object(vec![]),
+            ]),
+            build(vec![
+                Token::OpenBracket,
+                Token::Null,
+                Token::Comma,
+                Token::BoolTrue,
+                Token::Comma,
+                Token::BoolFalse,
+                Token::Comma,
+                Token::StringLiteral("s".to_string()),
+                Token::Comma,
+                Token::Number(JsonNumber::from(1.5)),
+                Token::Comma,
+                Token::OpenBracket,
+                Token::ClosedBracket,
+                Token::Comma,
+                Token::OpenBrace,
+                Token::ClosedBrace,
+                Token::ClosedBracket,
+            ])
+        );
+    }
+
+    #[test]
+    fn should_build_nested_containers() {
+        assert_eq!(
+            JsonValue::Object(vec![(
+                "key".to_string(),
+                JsonValue::Array(vec![JsonValue::Object(vec![(
+                    "inner".to_string(),
+                    JsonValue::Null
+                )])])
+            )]),
+            build(vec![
+                Token::OpenBrace,
+                Token::StringLiteral("key".to_string()),
+                Token::Column,
+                Token::OpenBracket,
+                Token::OpenBrace,
+                Token::StringLiteral("inner".to_string()),
+                Token::Column,
+                Token::Null,
+                Token::ClosedBrace,
+                Token::ClosedBracket,
+                Token::ClosedBrace,
+            ])
+        );
+    }
+}
@@ -0,0 +1,259 @@
+use super::{
+    error::JSONError,
+    events::JsonVisitor,
+    lexer::{Scanner, CHUNK_SIZE},
+    options::ParserOptions,
+    parser::State,
+    token::Position,
+};
+
+// validates a document handed over in chunks as they arrive, for input that
+// cannot be read through a blocking `BufRead`; the visitor is told about every
+// event as soon as it is known, and an error is returned by the first call
+// to `feed` that reveals it, without waiting for the rest of the input
+pub struct PushParser<V = ()> {
+    scanner: Scanner,
+    state: State,
+    visitor: V,
+    end: Position,
+    // once the input is known to be invalid, every later call fails the same way
+    error: Option<JSONError>,
+}
+
+impl PushParser {
+    pub fn new(options: &ParserOptions) -> PushParser {
+        PushParser::with_visitor(options, ())
+    }
+}
+
+impl<V: JsonVisitor> PushParser<V> {
+    pub fn with_visitor(options: &ParserOptions, visitor: V) -> PushParser<V> {
+        PushParser {
+            scanner: Scanner::new(options),
+            state: State::new(options),
+            visitor,
+            end: Position::start(),
+            error: None,
+        }
+    }
+
+    pub fn get_ref(&self) -> &V {
+        &self.visitor
+    }
+
+    pub fn get_mut(&mut self) -> &mut V {
+        &mut self.visitor
+    }
+
+    // whether a whole document has been read, anything fed after it other than
+    // whitespace is an error
+    pub fn is_complete(&self) -> bool {
+        self.state.is_done()
+    }
+
+    pub fn feed(&mut self, mut input: &[u8]) -> Result<(), JSONError> {
+        // a slice at a time, each parsed before the next is lexed, so that an
+        // error or a limit is found without lexing the rest of the input
+        loop {
+            let (slice, rest) = input.split_at(input.len().min(CHUNK_SIZE));
+            self.check(|parser| parser.scanner.push(slice))?;
+            if rest.is_empty() {
+                return Ok(());
+            }
+            input = rest;
+        }
+    }
+
+    // tells the parser that the input is over, returning the visitor once the
+    // document is known to be complete
+    pub fn finish(mut self) -> Result<V, JSONError> {
+        self.check(|parser| parser.scanner.finish())?;
+        self.state.finish(self.end)?;
+        Ok(self.visitor)
+    }
+
+    fn check<F>(&mut self, lex: F) -> Result<(), JSONError>
+    where
+        F: FnOnce(&mut PushParser<V>) -> Result<(), JSONError>,
+    {
+        if let Some(err) = &self.error {
+            return Err(err.clone());
+        }
+        // the tokens lexed before an error are parsed first, as they can hold
+        // an earlier one
+        let lexed = lex(self);
+        let result = self.parse_pending().and(lexed);
+        if let Err(err) = &result {
+            self.error = Some(err.clone());
+        }
+        result
+    }
+
+    fn parse_pending(&mut self) -> Result<(), JSONError> {
+        while let Some(token) = self.scanner.pop() {
+            self.end = token.span.end;
+            self.state.step(token, &mut self.visitor)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod push_tests {
+    use std::{collections::VecDeque, iter};
+
+    use super::CHUNK_SIZE;
+    use crate::parser::{
+        test_utils::describe, Dialect, JSONErrorKind, JsonEvent, JsonNumber, JsonValue,
+        ParserOptions, PushParser, Token, ValueBuilder,
+    };
+
+    fn feed_all(chunks: &[&[u8]]) -> Result<(), (JSONErrorKind, usize, usize)> {
+        let mut parser = PushParser::new(&ParserOptions::default());
+        for chunk in chunks {
+            parser.feed(chunk).map_err(|err| describe(&err))?;
+        }
+        parser.finish().map_err(|err| describe(&err))
+    }
+
+    #[test]
+    fn should_validate_document_fed_in_chunks() {
+        assert_eq!(Ok(()), feed_all(&[b"{\"ke", b"y\": [1", b"2, tr", b"ue]}"]));
+        assert_eq!(Ok(()), feed_all(&[b"[\"\xC3", b"\xA9\"", b"]"]));
+        assert_eq!(Ok(()), feed_all(&[b"[]", b""]));
+        // a byte at a time, so that characters and the encoding are cut
+        let utf16: Vec<u8> = "[\"é😊\"]"
+            .encode_utf16()
+            .flat_map(u16::to_le_bytes)
+            .collect();
+        let bytes: Vec<&[u8]> = utf16.chunks(1).collect();
+        assert_eq!(Ok(()), feed_all(&bytes));
+    }
+
+    #[test]
+    fn should_report_error_before_input_is_over() {
+        let mut parser = PushParser::new(&ParserOptions::default());
+        assert_eq!(Ok(()), parser.feed(b"{\"a\": 1"));
+        let err = parser.feed(b" \"b\": 2}").unwrap_err();
+        assert_eq!(
+            (
+                JSONErrorKind::UnexpectedToken {
+                    found: Token::StringLiteral("b".to_string()),
+                    expected: Some("',' or '}'")
+                },
+                1,
+                9
+            ),
+            describe(&err)
+        );
+        assert_eq!(Err(err.clone()), parser.feed(b"{}"));
+        assert_eq!(Err(err), parser.finish());
+    }
+
+    #[test]
+    fn should_report_tokens_errors_in_order() {
+        assert_eq!(
+            Err((
+                JSONErrorKind::UnexpectedToken {
+                    found: Token::ClosedBracket,
+                    expected: Some("the end of the document")
+                },
+                1,
+                3
+            )),
+            feed_all(&[b"[]] @"])
+        );
+        assert_eq!(
+            Err((JSONErrorKind::InvalidUtf8, 1, 3)),
+            feed_all(&[b"[\"\xC3", b"\""])
+        );
+    }
+
+    #[test]
+    fn should_stop_lexing_a_chunk_at_the_first_error() {
+        let input = [b"]".as_slice(), &[b'['; 20 * CHUNK_SIZE]].concat();
+        let mut parser = PushParser::new(&ParserOptions::default());
+        assert_eq!(
+            Err((
+                JSONErrorKind::UnexpectedToken {
+                    found: Token::ClosedBracket,
+                    expected: Some("'{' or '['")
+                },
+                1,
+                1
+            )),
+            parser.feed(&input).map_err(|err| describe(&err))
+        );
+        assert!(iter::from_fn(|| parser.scanner.pop()).count() < CHUNK_SIZE);
+
+        let mut parser = PushParser::new(&ParserOptions::new().max_depth(10));
+        let err = parser.feed(&input[1..]).unwrap_err();
+        assert_eq!(JSONErrorKind::DepthLimitExceeded(10), *err.kind());
+        assert!(iter::from_fn(|| parser.scanner.pop()).count() < CHUNK_SIZE);
+    }
+
+    #[test]
+    fn should_report_truncated_document_on_finish() {
+        assert_eq!(
+            Err((JSONErrorKind::UnexpectedEof, 1, 4)),
+            feed_all(&[b"[1,"])
+        );
+        assert_eq!(Err((JSONErrorKind::UnexpectedEof, 1, 1)), feed_all(&[]));
+        assert_eq!(
+            Err((JSONErrorKind::UnexpectedEof, 1, 5)),
+            feed_all(&[b"[\"ab"])
+        );
+    }
+
+    #[test]
+    fn should_tell_when_document_is_complete() {
+        let options = ParserOptions::new().dialect(Dialect::Strict);
+        let mut parser = PushParser::new(&options);
+        parser.feed(b"[1, 2").unwrap();
+        assert!(!parser.is_complete());
+        parser.feed(b"]").unwrap();
+        assert!(parser.is_complete());
+        // a number is only complete once something follows it
+        let mut parser = PushParser::new(&options);
+        parser.feed(b"12").unwrap();
+        assert!(!parser.is_complete());
+        parser.finish().unwrap();
+    }
+
+    #[test]
+    fn should_build_value_with_visitor() {
+        let mut parser = PushParser::with_visitor(&ParserOptions::default(), ValueBuilder::new());
+        parser.feed(b"{\"a\": [tr").unwrap();
+        parser.feed(b"ue, null]}\n").unwrap();
+        let value = parser.finish().unwrap().finish();
+        assert_eq!(
+            Some(JsonValue::Object(vec![(
+                "a".to_string(),
+                JsonValue::Array(vec![JsonValue::Bool(true), JsonValue::Null])
+            )])),
+            value
+        );
+    }
+
+    #[test]
+    fn should_report_events_as_they_are_known() {
+        let mut parser = PushParser::with_visitor(&ParserOptions::default(), VecDeque::new());
+        parser.feed(b"[\"a\", 1").unwrap();
+        assert_eq!(
+            vec![
+                JsonEvent::StartArray,
+                JsonEvent::Value(JsonValue::String("a".to_string()))
+            ],
+            parser.get_mut().drain(..).collect::<Vec<JsonEvent>>()
+        );
+        parser.feed(b"0]").unwrap();
+        assert_eq!(
+            vec![
+                JsonEvent::Value(JsonValue::Number(JsonNumber::from(10.))),
+                JsonEvent::EndArray
+            ],
+            parser.get_mut().drain(..).collect::<Vec<JsonEvent>>()
+        );
+        assert!(parser.finish().unwrap().is_empty());
+    }
+}
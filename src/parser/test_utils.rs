@@ -0,0 +1,29 @@
+use super::{
+    error::{JSONError, JSONErrorKind},
+    token::{Position, Span, SpannedToken, Token},
+};
+
+// lays the tokens out on a single line, one column each, as the lexer yields them
+pub fn spanned(tokens: Vec<Token>) -> Vec<Result<SpannedToken, JSONError>> {
+    tokens
+        .into_iter()
+        .enumerate()
+        .map(|(i, token)| {
+            Ok(SpannedToken::new(
+                token,
+                Span::new(Position::new(i, 1, i + 1), Position::new(i + 1, 1, i + 2)),
+            ))
+        })
+        .collect()
+}
+
+pub fn describe(error: &JSONError) -> (JSONErrorKind, usize, usize) {
+    (error.kind().clone(), error.line(), error.column())
+}
+
+pub fn unexpected(found: Token, expected: &'static str) -> JSONErrorKind {
+    JSONErrorKind::UnexpectedToken {
+        found,
+        expected: Some(expected),
+    }
+}
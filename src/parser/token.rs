@@ -1,6 +1,8 @@
 use core::fmt;
 
-#[derive(PartialEq, Debug)]
+use super::number::JsonNumber;
+
+#[derive(PartialEq, Debug, Clone)]
 pub enum Token {
     OpenBrace,
     ClosedBrace,
@@ -10,12 +12,74 @@ pub enum Token {
     Column,
     Comma,
     StringLiteral(String),
-    Number(f64),
+    // a JSON5 unquoted key
+    Identifier(String),
+    // the RFC 7464 separator, only found between documents of a sequence
+    RecordSeparator,
+    // as written in the document
+    Number(JsonNumber),
     BoolTrue,
     BoolFalse,
     Null
 }
 
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub struct Position {
+    pub offset: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Position {
+    pub fn new(offset: usize, line: usize, column: usize) -> Position {
+        Position {
+            offset,
+            line,
+            column,
+        }
+    }
+
+    pub fn start() -> Position {
+        Position::new(0, 1, 1)
+    }
+
+    pub fn advance(self, c: char) -> Position {
+        if c == '\n' {
+            Position::new(self.offset + 1, self.line + 1, 1)
+        } else {
+            Position::new(self.offset + c.len_utf8(), self.line, self.column + 1)
+        }
+    }
+}
+
+#[derive(PartialEq, Eq, Debug, Clone, Copy)]
+pub struct Span {
+    pub start: Position,
+    pub end: Position,
+}
+
+impl Span {
+    pub fn new(start: Position, end: Position) -> Span {
+        Span { start, end }
+    }
+
+    pub fn at(position: Position) -> Span {
+        Span::new(position, position)
+    }
+}
+
+#[derive(PartialEq, Debug, Clone)]
+pub struct SpannedToken {
+    pub token: Token,
+    pub span: Span,
+}
+
+impl SpannedToken {
+    pub fn new(token: Token, span: Span) -> SpannedToken {
+        SpannedToken { token, span }
+    }
+}
+
 const OPEN_BRACE: &str= "{";
 const CLOSED_BRACE: &str = "}";
 const NEW_LINE: &str = "\n"; //TODO: make sure this works on windows too
@@ -37,6 +101,8 @@ impl fmt::Display for Token {
             Token::BoolTrue | Token::BoolFalse => String::from("<boolean>"),
             Token::Null => String::from("<null>"),
             Token::StringLiteral(_) => String::from("<string literal>"),
+            Token::Identifier(_) => String::from("<identifier>"),
+            Token::RecordSeparator => String::from("<record separator>"),
             Token::OpenBracket => String::from(OPEN_BRACKET),
             Token::ClosedBracket => String::from(CLOSED_BRACKET),
         };
@@ -0,0 +1,217 @@
+use super::{
+    events::JsonVisitor,
+    number::JsonNumber,
+    options::{DuplicateKeys, ParserOptions},
+    token::Span,
+};
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum JsonValue {
+    Null,
+    Bool(bool),
+    Number(JsonNumber),
+    String(String),
+    Array(Vec<JsonValue>),
+    Object(Vec<(String, JsonValue)>),
+}
+
+impl JsonValue {
+    pub fn is_null(&self) -> bool {
+        *self == JsonValue::Null
+    }
+
+    pub fn as_bool(&self) -> Option<bool> {
+        match self {
+            JsonValue::Bool(b) => Some(*b),
+            _ => None,
+        }
+    }
+
+    pub fn as_number(&self) -> Option<&JsonNumber> {
+        match self {
+            JsonValue::Number(n) => Some(n),
+            _ => None,
+        }
+    }
+
+    pub fn as_f64(&self) -> Option<f64> {
+        self.as_number().map(JsonNumber::as_f64)
+    }
+
+    pub fn as_i64(&self) -> Option<i64> {
+        self.as_number()?.as_i64()
+    }
+
+    pub fn as_u64(&self) -> Option<u64> {
+        self.as_number()?.as_u64()
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            JsonValue::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_array(&self) -> Option<&Vec<JsonValue>> {
+        match self {
+            JsonValue::Array(values) => Some(values),
+            _ => None,
+        }
+    }
+
+    pub fn as_object(&self) -> Option<&Vec<(String, JsonValue)>> {
+        match self {
+            JsonValue::Object(members) => Some(members),
+            _ => None,
+        }
+    }
+
+    // members keep their source order, so with duplicated keys the last one wins
+    pub fn get(&self, key: &str) -> Option<&JsonValue> {
+        self.as_object()?
+            .iter()
+            .rev()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v)
+    }
+}
+
+enum Partial {
+    // the key of the member being read, and the index of the earlier member
+    // with the same key when only one of them is kept
+    Object(Vec<(String, JsonValue)>, Option<String>, Option<usize>),
+    Array(Vec<JsonValue>),
+}
+
+// a visitor building the `JsonValue` of the document
+#[derive(Default)]
+pub struct ValueBuilder {
+    stack: Vec<Partial>,
+    root: Option<JsonValue>,
+    duplicate_keys: DuplicateKeys,
+}
+
+impl ValueBuilder {
+    pub fn new() -> ValueBuilder {
+        ValueBuilder::default()
+    }
+
+    // keeps one member per key when the options say which one wins
+    pub fn with_options(options: &ParserOptions) -> ValueBuilder {
+        ValueBuilder {
+            duplicate_keys: options.duplicate_keys,
+            ..ValueBuilder::default()
+        }
+    }
+
+    pub fn finish(self) -> Option<JsonValue> {
+        self.root
+    }
+
+    fn add(&mut self, value: JsonValue) {
+        match self.stack.last_mut() {
+            Some(Partial::Object(members, pending_key, earlier)) => {
+                match (pending_key.take(), earlier.take()) {
+                    (Some(_), Some(i)) if self.duplicate_keys == DuplicateKeys::LastWins => {
+                        members[i].1 = value
+                    }
+                    (Some(_), Some(_)) => {}
+                    (Some(key), None) => members.push((key, value)),
+                    (None, _) => {}
+                }
+            }
+            Some(Partial::Array(values)) => values.push(value),
+            None => self.root = Some(value),
+        }
+    }
+
+    fn close(&mut self) {
+        let value = match self.stack.pop() {
+            Some(Partial::Object(members, ..)) => JsonValue::Object(members),
+            Some(Partial::Array(values)) => JsonValue::Array(values),
+            None => return,
+        };
+        self.add(value);
+    }
+}
+
+impl JsonVisitor for ValueBuilder {
+    fn start_object(&mut self) {
+        self.stack.push(Partial::Object(vec![], None, None));
+    }
+
+    fn duplicate_key(&mut self, key: &str, _span: Span) {
+        if !matches!(self.duplicate_keys, DuplicateKeys::FirstWins | DuplicateKeys::LastWins) {
+            return;
+        }
+        if let Some(Partial::Object(members, _, earlier)) = self.stack.last_mut() {
+            *earlier = members.iter().position(|(k, _)| k == key);
+        }
+    }
+
+    fn key(&mut self, key: String) {
+        if let Some(Partial::Object(_, pending_key, _)) = self.stack.last_mut() {
+            *pending_key = Some(key);
+        }
+    }
+
+    fn start_array(&mut self) {
+        self.stack.push(Partial::Array(vec![]));
+    }
+
+    fn value(&mut self, value: JsonValue) {
+        self.add(value);
+    }
+
+    fn end_array(&mut self) {
+        self.close();
+    }
+
+    fn end_object(&mut self) {
+        self.close();
+    }
+}
+
+#[cfg(test)]
+mod value_tests {
+    use super::*;
+
+    #[test]
+    fn should_get_last_member_with_key() {
+        let value = JsonValue::Object(vec![
+            ("a".to_string(), JsonValue::Number(JsonNumber::from(1.))),
+            ("b".to_string(), JsonValue::Null),
+            ("a".to_string(), JsonValue::Number(JsonNumber::from(2.))),
+        ]);
+        assert_eq!(Some(&JsonValue::Number(JsonNumber::from(2.))), value.get("a"));
+        assert_eq!(Some(&JsonValue::Null), value.get("b"));
+        assert_eq!(None, value.get("c"));
+    }
+
+    #[test]
+    fn should_not_get_member_of_non_object() {
+        let value = JsonValue::Array(vec![JsonValue::Null]);
+        assert_eq!(None, value.get("a"));
+    }
+
+    #[test]
+    fn should_build_nested_values() {
+        let mut builder = ValueBuilder::new();
+        builder.start_object();
+        builder.key("arr".to_string());
+        builder.start_array();
+        builder.value(JsonValue::Bool(true));
+        builder.start_object();
+        builder.end_object();
+        builder.end_array();
+        builder.end_object();
+        assert_eq!(
+            Some(JsonValue::Object(vec![(
+                "arr".to_string(),
+                JsonValue::Array(vec![JsonValue::Bool(true), JsonValue::Object(vec![])])
+            )])),
+            builder.finish()
+        );
+    }
+}
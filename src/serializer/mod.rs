@@ -0,0 +1,339 @@
+use std::{
+    io::{self, Write},
+    slice, vec,
+};
+
+use crate::parser::{JsonNumber, JsonValue, Token};
+
+mod pretty;
+pub use pretty::{to_string_pretty, to_writer_pretty, Indent, PrettyConfig};
+
+pub fn write_string<W: Write>(writer: &mut W, s: &str) -> io::Result<()> {
+    writer.write_all(b"\"")?;
+    let mut start = 0;
+    for (idx, c) in s.char_indices() {
+        let escaped = match c {
+            '"' => "\\\"",
+            '\\' => "\\\\",
+            '\u{08}' => "\\b",
+            '\u{0C}' => "\\f",
+            '\n' => "\\n",
+            '\r' => "\\r",
+            '\t' => "\\t",
+            '\u{00}'..='\u{1F}' => "",
+            _ => continue,
+        };
+        writer.write_all(&s.as_bytes()[start..idx])?;
+        if escaped.is_empty() {
+            write!(writer, "\\u{:04x}", c as u32)?;
+        } else {
+            writer.write_all(escaped.as_bytes())?;
+        }
+        start = idx + c.len_utf8();
+    }
+    writer.write_all(&s.as_bytes()[start..])?;
+    writer.write_all(b"\"")
+}
+
+// like JSON.stringify, numbers that JSON cannot represent become null
+pub fn write_number<W: Write>(writer: &mut W, n: &JsonNumber) -> io::Result<()> {
+    if n.is_finite() {
+        writer.write_all(n.as_str().as_bytes())
+    } else {
+        writer.write_all(b"null")
+    }
+}
+
+// a step of `walk`; `index` counts the values of each container from 0 and
+// `depth` is 0 for the value walked
+enum Visit<'a> {
+    Value {
+        value: &'a JsonValue,
+        key: Option<&'a str>,
+        index: usize,
+        depth: usize,
+    },
+    // after the last value of a container the walk descended into
+    End {
+        value: &'a JsonValue,
+        depth: usize,
+    },
+}
+
+enum Children<'a> {
+    Elements(slice::Iter<'a, JsonValue>),
+    Members(slice::Iter<'a, (String, JsonValue)>),
+    SortedMembers(vec::IntoIter<&'a (String, JsonValue)>),
+}
+
+impl<'a> Children<'a> {
+    fn of(value: &'a JsonValue, sort_keys: bool) -> Children<'a> {
+        match value {
+            JsonValue::Object(members) if sort_keys => {
+                let mut members: Vec<_> = members.iter().collect();
+                members.sort_by(|(a, _), (b, _)| a.cmp(b));
+                Children::SortedMembers(members.into_iter())
+            }
+            JsonValue::Object(members) => Children::Members(members.iter()),
+            JsonValue::Array(values) => Children::Elements(values.iter()),
+            _ => Children::Elements([].iter()),
+        }
+    }
+}
+
+impl<'a> Iterator for Children<'a> {
+    type Item = (Option<&'a str>, &'a JsonValue);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match self {
+            Children::Elements(values) => values.next().map(|value| (None, value)),
+            Children::Members(members) => members.next().map(|(k, v)| (Some(k.as_str()), v)),
+            Children::SortedMembers(members) => members.next().map(|(k, v)| (Some(k.as_str()), v)),
+        }
+    }
+}
+
+// visits `value` depth first, descending into the containers for which `visit`
+// returns true; the open containers are kept on the heap rather than on the
+// call stack, which deeply nested documents would overflow
+fn walk<'a, F>(value: &'a JsonValue, sort_keys: bool, mut visit: F) -> io::Result<()>
+where
+    F: FnMut(Visit<'a>) -> io::Result<bool>,
+{
+    let mut open = vec![];
+    let root = Visit::Value {
+        value,
+        key: None,
+        index: 0,
+        depth: 0,
+    };
+    if visit(root)? {
+        open.push((value, Children::of(value, sort_keys), 0));
+    }
+    loop {
+        let depth = open.len();
+        let (container, children, visited) = match open.last_mut() {
+            Some(container) => container,
+            None => return Ok(()),
+        };
+        match children.next() {
+            Some((key, value)) => {
+                let index = *visited;
+                *visited += 1;
+                if visit(Visit::Value {
+                    value,
+                    key,
+                    index,
+                    depth,
+                })? {
+                    open.push((value, Children::of(value, sort_keys), 0));
+                }
+            }
+            None => {
+                let value = *container;
+                open.pop();
+                visit(Visit::End {
+                    value,
+                    depth: depth - 1,
+                })?;
+            }
+        }
+    }
+}
+
+// writes a scalar, or the opening bracket of a container, returning whether
+// there is a container to write the values of
+fn write_start<W: Write>(writer: &mut W, value: &JsonValue) -> io::Result<bool> {
+    match value {
+        JsonValue::Null => writer.write_all(b"null")?,
+        JsonValue::Bool(true) => writer.write_all(b"true")?,
+        JsonValue::Bool(false) => writer.write_all(b"false")?,
+        JsonValue::Number(n) => write_number(writer, n)?,
+        JsonValue::String(s) => write_string(writer, s)?,
+        JsonValue::Array(_) => {
+            writer.write_all(b"[")?;
+            return Ok(true);
+        }
+        JsonValue::Object(_) => {
+            writer.write_all(b"{")?;
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+fn write_end<W: Write>(writer: &mut W, value: &JsonValue) -> io::Result<bool> {
+    match value {
+        JsonValue::Array(_) => writer.write_all(b"]")?,
+        _ => writer.write_all(b"}")?,
+    }
+    Ok(false)
+}
+
+fn write_value<W: Write>(writer: &mut W, value: &JsonValue) -> io::Result<()> {
+    walk(value, false, |visit| match visit {
+        Visit::Value {
+            value, key, index, ..
+        } => {
+            if index > 0 {
+                writer.write_all(b",")?;
+            }
+            if let Some(key) = key {
+                write_string(writer, key)?;
+                writer.write_all(b":")?;
+            }
+            write_start(writer, value)
+        }
+        Visit::End { value, .. } => write_end(writer, value),
+    })
+}
+
+pub fn to_writer<W: Write>(value: &JsonValue, mut writer: W) -> io::Result<()> {
+    write_value(&mut writer, value)
+}
+
+pub fn to_string(value: &JsonValue) -> String {
+    let mut buf = Vec::new();
+    write_value(&mut buf, value).expect("writing to a Vec cannot fail");
+    String::from_utf8(buf).expect("the serializer only writes UTF-8")
+}
+
+pub fn write_token<W: Write>(writer: &mut W, token: &Token) -> io::Result<()> {
+    match token {
+        Token::OpenBrace => writer.write_all(b"{"),
+        Token::ClosedBrace => writer.write_all(b"}"),
+        Token::OpenBracket => writer.write_all(b"["),
+        Token::ClosedBracket => writer.write_all(b"]"),
+        Token::Column => writer.write_all(b":"),
+        Token::Comma => writer.write_all(b","),
+        Token::NewLine => Ok(()),
+        Token::RecordSeparator => writer.write_all(b"\x1E"),
+        Token::StringLiteral(s) | Token::Identifier(s) => write_string(writer, s),
+        Token::Number(n) => write_number(writer, n),
+        Token::BoolTrue => writer.write_all(b"true"),
+        Token::BoolFalse => writer.write_all(b"false"),
+        Token::Null => writer.write_all(b"null"),
+    }
+}
+
+pub fn tokens_to_writer<'a, I, W>(tokens: I, mut writer: W) -> io::Result<()>
+where
+    I: IntoIterator<Item = &'a Token>,
+    W: Write,
+{
+    for token in tokens {
+        write_token(&mut writer, token)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod serializer_tests {
+    use super::*;
+    use crate::parser::{lex, parse_value};
+
+    fn number_to_string(n: f64) -> String {
+        let mut buf = Vec::new();
+        write_number(&mut buf, &n.into()).unwrap();
+        String::from_utf8(buf).unwrap()
+    }
+
+    #[test]
+    fn should_serialize_scalars() {
+        assert_eq!("null", to_string(&JsonValue::Null));
+        assert_eq!("true", to_string(&JsonValue::Bool(true)));
+        assert_eq!("false", to_string(&JsonValue::Bool(false)));
+        assert_eq!("\"abc\"", to_string(&JsonValue::String("abc".to_string())));
+    }
+
+    #[test]
+    fn should_serialize_containers_compactly() {
+        let value = JsonValue::Object(vec![
+            ("a".to_string(), JsonValue::Array(vec![])),
+            (
+                "b".to_string(),
+                JsonValue::Array(vec![
+                    JsonValue::Number(JsonNumber::from(1.)),
+                    JsonValue::Object(vec![]),
+                ]),
+            ),
+        ]);
+        assert_eq!("{\"a\":[],\"b\":[1,{}]}", to_string(&value));
+    }
+
+    #[test]
+    fn should_escape_strings() {
+        let value = JsonValue::String("q\"b\\s/\u{08}\u{0C}\n\r\t\u{01}\u{1F}😊".to_string());
+        assert_eq!(
+            "\"q\\\"b\\\\s/\\b\\f\\n\\r\\t\\u0001\\u001f😊\"",
+            to_string(&value)
+        );
+    }
+
+    #[test]
+    fn should_write_integers_without_fraction() {
+        assert_eq!("0", number_to_string(0.));
+        assert_eq!("-0", number_to_string(-0.));
+        assert_eq!("42", number_to_string(42.));
+        assert_eq!("-1234567890", number_to_string(-1234567890.));
+    }
+
+    #[test]
+    fn should_write_numbers_that_round_trip() {
+        for n in [
+            0.1,
+            -2.5e-5,
+            1e-7,
+            1e17,
+            123456789012345680000.,
+            f64::MAX,
+            f64::MIN_POSITIVE,
+            5e-324,
+            std::f64::consts::PI,
+        ] {
+            let written = number_to_string(n);
+            assert_eq!(n, written.parse::<f64>().unwrap(), "{}", written);
+            let tokens = lex(format!("[{}]", written).as_bytes()).unwrap();
+            assert_eq!(Token::Number(n.into()), tokens[1].token, "{}", written);
+        }
+    }
+
+    #[test]
+    fn should_write_numbers_as_written() {
+        let input = "[12345678901234567890,0.1000000000000000000001,1E+2,-0.0]";
+        assert_eq!(input, to_string(&parse_value(input.as_bytes()).unwrap()));
+    }
+
+    #[test]
+    fn should_write_non_finite_numbers_as_null() {
+        assert_eq!("null", number_to_string(f64::INFINITY));
+        assert_eq!("null", number_to_string(f64::NAN));
+    }
+
+    #[test]
+    fn should_round_trip_parsed_document() {
+        let input = "{\"key\": [1, 2.5, -3e-9, true, false, null, \"v\"], \"obj\": {\"in\": {}}}";
+        let value = parse_value(input.as_bytes()).unwrap();
+        let written = to_string(&value);
+        assert_eq!(
+            "{\"key\":[1,2.5,-3e-9,true,false,null,\"v\"],\"obj\":{\"in\":{}}}",
+            written
+        );
+        assert_eq!(value, parse_value(written.as_bytes()).unwrap());
+    }
+
+    #[test]
+    fn should_write_token_stream_without_new_lines() {
+        let tokens: Vec<Token> = lex("{\n  \"a\": [1, true],\n  \"b\": null\n}\n".as_bytes())
+            .unwrap()
+            .into_iter()
+            .map(|spanned| spanned.token)
+            .collect();
+        let mut buf = Vec::new();
+        tokens_to_writer(&tokens, &mut buf).unwrap();
+        assert_eq!(
+            "{\"a\":[1,true],\"b\":null}",
+            String::from_utf8(buf).unwrap()
+        );
+    }
+}
@@ -0,0 +1,296 @@
+use std::io::{self, Write};
+
+use super::{walk, write_end, write_start, write_string, Visit};
+use crate::parser::JsonValue;
+
+// tabs are counted as this many columns when checking `compact_width`
+const TAB_WIDTH: usize = 4;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Indent {
+    Spaces(usize),
+    Tab,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrettyConfig {
+    pub indent: Indent,
+    pub sort_keys: bool,
+    pub space_after_colon: bool,
+    // arrays and objects that fit in this many columns are kept on one line
+    pub compact_width: Option<usize>,
+}
+
+impl Default for PrettyConfig {
+    fn default() -> PrettyConfig {
+        PrettyConfig {
+            indent: Indent::Spaces(2),
+            sort_keys: false,
+            space_after_colon: true,
+            compact_width: None,
+        }
+    }
+}
+
+// a writer that gives up as soon as more than `limit` characters are written to
+// it, used to check whether a value fits in `compact_width` without rendering
+// all of it
+struct LimitedWriter {
+    buf: Vec<u8>,
+    limit: usize,
+    width: usize,
+}
+
+impl Write for LimitedWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        // every UTF-8 character has one byte that is not a continuation byte
+        let width = buf.iter().filter(|b| **b & 0xC0 != 0x80).count();
+        if self.width + width > self.limit {
+            return Err(io::Error::new(io::ErrorKind::WriteZero, "limit reached"));
+        }
+        self.buf.extend_from_slice(buf);
+        self.width += width;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+struct PrettyPrinter<'a> {
+    config: &'a PrettyConfig,
+}
+
+impl PrettyPrinter<'_> {
+    fn write_colon<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        if self.config.space_after_colon {
+            writer.write_all(b": ")
+        } else {
+            writer.write_all(b":")
+        }
+    }
+
+    fn write_indent<W: Write>(&self, writer: &mut W, depth: usize) -> io::Result<()> {
+        match self.config.indent {
+            Indent::Spaces(width) => writer.write_all(" ".repeat(width * depth).as_bytes()),
+            Indent::Tab => writer.write_all("\t".repeat(depth).as_bytes()),
+        }
+    }
+
+    fn indent_width(&self, depth: usize) -> usize {
+        match self.config.indent {
+            Indent::Spaces(width) => width * depth,
+            Indent::Tab => TAB_WIDTH * depth,
+        }
+    }
+
+    fn write_inline<W: Write>(&self, writer: &mut W, value: &JsonValue) -> io::Result<()> {
+        walk(value, self.config.sort_keys, |visit| match visit {
+            Visit::Value {
+                value, key, index, ..
+            } => {
+                if index > 0 {
+                    writer.write_all(b", ")?;
+                }
+                if let Some(key) = key {
+                    write_string(writer, key)?;
+                    self.write_colon(writer)?;
+                }
+                write_start(writer, value)
+            }
+            Visit::End { value, .. } => write_end(writer, value),
+        })
+    }
+
+    fn fits_inline(&self, value: &JsonValue, column: usize) -> Option<Vec<u8>> {
+        let limit = self.config.compact_width?.checked_sub(column)?;
+        let mut writer = LimitedWriter {
+            buf: vec![],
+            limit,
+            width: 0,
+        };
+        self.write_inline(&mut writer, value).ok()?;
+        Some(writer.buf)
+    }
+
+    fn write_value<W: Write>(&self, writer: &mut W, value: &JsonValue) -> io::Result<()> {
+        walk(value, self.config.sort_keys, |visit| match visit {
+            Visit::Value {
+                value,
+                key,
+                index,
+                depth,
+            } => {
+                // where the value starts on its line, after indentation and key
+                let mut column = 0;
+                if depth > 0 {
+                    if index > 0 {
+                        writer.write_all(b",\n")?;
+                    }
+                    self.write_indent(writer, depth)?;
+                    column = self.indent_width(depth);
+                }
+                if let Some(key) = key {
+                    let mut prefix = vec![];
+                    write_string(&mut prefix, key)?;
+                    self.write_colon(&mut prefix)?;
+                    writer.write_all(&prefix)?;
+                    column += String::from_utf8_lossy(&prefix).chars().count();
+                }
+                match value {
+                    JsonValue::Array(values) if values.is_empty() => writer.write_all(b"[]")?,
+                    JsonValue::Object(members) if members.is_empty() => writer.write_all(b"{}")?,
+                    JsonValue::Array(_) | JsonValue::Object(_) => {
+                        if let Some(inline) = self.fits_inline(value, column) {
+                            writer.write_all(&inline)?;
+                        } else {
+                            write_start(writer, value)?;
+                            writer.write_all(b"\n")?;
+                            return Ok(true);
+                        }
+                    }
+                    scalar => return write_start(writer, scalar),
+                }
+                Ok(false)
+            }
+            Visit::End { value, depth } => {
+                writer.write_all(b"\n")?;
+                self.write_indent(writer, depth)?;
+                write_end(writer, value)
+            }
+        })
+    }
+}
+
+pub fn to_writer_pretty<W: Write>(
+    value: &JsonValue,
+    mut writer: W,
+    config: &PrettyConfig,
+) -> io::Result<()> {
+    PrettyPrinter { config }.write_value(&mut writer, value)
+}
+
+pub fn to_string_pretty(value: &JsonValue, config: &PrettyConfig) -> String {
+    let mut buf = Vec::new();
+    to_writer_pretty(value, &mut buf, config).expect("writing to a Vec cannot fail");
+    String::from_utf8(buf).expect("the serializer only writes UTF-8")
+}
+
+#[cfg(test)]
+mod pretty_tests {
+    use super::*;
+    use crate::parser::{parse_value, JsonNumber};
+
+    const DOC: &str = "{\"b\": [1, {\"z\": null, \"a\": true}], \"a\": \"s\", \"e\": [], \"o\": {}}";
+
+    fn pretty(input: &str, config: &PrettyConfig) -> String {
+        to_string_pretty(&parse_value(input.as_bytes()).unwrap(), config)
+    }
+
+    #[test]
+    fn should_indent_with_two_spaces_by_default() {
+        assert_eq!(
+            pretty(DOC, &PrettyConfig::default()),
+            "{\n  \"b\": [\n    1,\n    {\n      \"z\": null,\n      \"a\": true\n    }\n  ],\n  \
+             \"a\": \"s\",\n  \"e\": [],\n  \"o\": {}\n}"
+        );
+    }
+
+    #[test]
+    fn should_indent_with_tabs() {
+        let config = PrettyConfig {
+            indent: Indent::Tab,
+            ..PrettyConfig::default()
+        };
+        assert_eq!(pretty("[1, [2]]", &config), "[\n\t1,\n\t[\n\t\t2\n\t]\n]");
+    }
+
+    #[test]
+    fn should_indent_with_custom_width() {
+        let config = PrettyConfig {
+            indent: Indent::Spaces(4),
+            ..PrettyConfig::default()
+        };
+        assert_eq!(pretty("{\"a\": 1}", &config), "{\n    \"a\": 1\n}");
+    }
+
+    #[test]
+    fn should_sort_keys_at_every_level() {
+        let config = PrettyConfig {
+            sort_keys: true,
+            compact_width: Some(80),
+            ..PrettyConfig::default()
+        };
+        assert_eq!(
+            pretty(DOC, &config),
+            "{\"a\": \"s\", \"b\": [1, {\"a\": true, \"z\": null}], \"e\": [], \"o\": {}}"
+        );
+    }
+
+    #[test]
+    fn should_omit_space_after_colon() {
+        let config = PrettyConfig {
+            space_after_colon: false,
+            ..PrettyConfig::default()
+        };
+        assert_eq!(pretty("{\"a\": {\"b\": 1}}", &config), "{\n  \"a\":{\n    \"b\":1\n  }\n}");
+    }
+
+    #[test]
+    fn should_keep_short_containers_on_one_line() {
+        let config = PrettyConfig {
+            compact_width: Some(20),
+            ..PrettyConfig::default()
+        };
+        assert_eq!(
+            pretty(
+                "{\"short\": [1, 2, 3], \"long\": [\"aaaaaaaa\", \"bbbbbbbb\"]}",
+                &config
+            ),
+            "{\n  \"short\": [1, 2, 3],\n  \"long\": [\n    \"aaaaaaaa\",\n    \"bbbbbbbb\"\n  ]\n}"
+        );
+    }
+
+    #[test]
+    fn should_count_indentation_and_key_in_compact_width() {
+        let config = PrettyConfig {
+            compact_width: Some(12),
+            ..PrettyConfig::default()
+        };
+        assert_eq!(pretty("[[1, 2, 3]]", &config), "[[1, 2, 3]]");
+        assert_eq!(
+            pretty("{\"k\": [1, 2, 3]}", &config),
+            "{\n  \"k\": [\n    1,\n    2,\n    3\n  ]\n}"
+        );
+    }
+
+    #[test]
+    fn should_count_compact_width_in_characters() {
+        let config = PrettyConfig {
+            compact_width: Some(16),
+            ..PrettyConfig::default()
+        };
+        assert_eq!(pretty("[\"ééé\", \"😊😊\"]", &config), "[\"ééé\", \"😊😊\"]");
+        // the line of "é" is 19 characters wide, without its comma
+        let config = PrettyConfig {
+            compact_width: Some(19),
+            ..config
+        };
+        assert_eq!(
+            pretty("{\"é\": [\"ééé\", \"😊\"], \"b\": 1}", &config),
+            "{\n  \"é\": [\"ééé\", \"😊\"],\n  \"b\": 1\n}"
+        );
+    }
+
+    #[test]
+    fn should_print_scalar_values() {
+        let config = PrettyConfig::default();
+        assert_eq!(
+            to_string_pretty(&JsonValue::String("a\nb".to_string()), &config),
+            "\"a\\nb\""
+        );
+        let one_and_a_half = JsonValue::Number(JsonNumber::from(1.5));
+        assert_eq!(to_string_pretty(&one_and_a_half, &config), "1.5");
+    }
+}